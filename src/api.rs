@@ -1,8 +1,14 @@
 use bitcoin::block::Header;
 use bitcoin::consensus::encode;
-use bitcoin::Transaction;
+use bitcoin::{BlockHash, CompactTarget, OutPoint, PrivateKey, ScriptBuf, Transaction};
 use misfit_core::block::generator::GenerateBlock;
+use misfit_core::block::info::{BlockInfo, SignetBlockInfo};
 use misfit_core::block::random::block::BlockParams;
+use misfit_core::block::random::budget::BudgetParams;
+use misfit_core::block::random::chain::ChainParams;
+use misfit_core::block::random::header::HeaderParams;
+use misfit_core::block::signet::SignetParams;
+use misfit_core::breakers::raw::{ParseTrace, RawMutation, RawMutator};
 use misfit_core::breakers::{block, decoder_tools, transaction};
 use misfit_core::regtest_pack::regtest::RegtestManager;
 use misfit_core::transaction::generator::GenerateTx;
@@ -14,44 +20,142 @@ use std::collections::HashSet;
 pub struct Generator {}
 
 impl Generator {
-    pub fn block(tx_count: u32) -> String {
+    pub fn block(tx_count: u32) -> BlockInfo {
         let mut txs: Vec<Transaction> = vec![];
-        let mut raw_tx: Vec<String> = vec![];
-        let mut tx_ids: Vec<String> = vec![];
 
         for _c in 0..tx_count {
-        let mut tx_params = TxParams::default();
-        let tx_input_params = InputParams {
-            script_params: Some(ScriptParams {
-                script_type: Some(ScriptTypes::P2WPKH),
-                private_key: None,
-            }),
-            ..Default::default()
-        };
-
-        tx_params.input = Some(tx_input_params);
-
-        let tx_info = GenerateTx::valid_random(tx_params);
-        let raw_transaction = hex::encode(encode::serialize(&tx_info)).to_string();
-        let txid = tx_info.compute_txid().to_string();
+            let tx_params = TxParams {
+                input: Some(InputParams {
+                    script_params: Some(ScriptParams {
+                        script_type: Some(ScriptTypes::P2WPKH),
+                        private_key: None,
+                    }),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            };
 
-        txs.push(tx_info);
-        raw_tx.push(raw_transaction);
-        tx_ids.push(txid);
-}
+            txs.push(GenerateTx::valid_random(tx_params));
+        }
 
         let block = GenerateBlock::valid_random(BlockParams {
             header: None,
             txs: Some(txs),
         });
 
-        [
-            format!("{:#?} ", block.header),
-            format!("Block Header encoded: {:#?}", encode::serialize_hex(&block.header)),
-            format!("Raw txs: {:#?}", raw_tx),
-            format!("TxID: {:#?}", tx_ids),
-        ]
-        .join("\n---\n")
+        BlockInfo::from(&block)
+    }
+
+    pub fn block_with_budget(
+        weight: Option<u64>,
+        sigops: Option<u64>,
+    ) -> Result<BlockInfo, Box<dyn std::error::Error>> {
+        let block = GenerateBlock::with_budget(BudgetParams {
+            weight,
+            sigops,
+            ..Default::default()
+        })
+        .map_err(|e| format!("Error generating block: {}", e))?;
+
+        Ok(BlockInfo::from(&block))
+    }
+
+    pub fn signet_block(
+        challenge: Option<String>,
+        private_keys: Vec<String>,
+        bits: Option<String>,
+        break_mode: Option<String>,
+    ) -> Result<SignetBlockInfo, Box<dyn std::error::Error>> {
+        let challenge = challenge
+            .map(|hex| ScriptBuf::from_hex(&hex))
+            .transpose()
+            .map_err(|e| format!("Error decoding challenge: {}", e))?;
+        let private_keys = private_keys
+            .iter()
+            .map(|wif| PrivateKey::from_wif(wif))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("Error decoding private key: {}", e))?;
+        let private_keys = (!private_keys.is_empty()).then_some(private_keys);
+        let bits = bits
+            .map(|hex| CompactTarget::from_unprefixed_hex(hex.trim_start_matches("0x")))
+            .transpose()
+            .map_err(|e| format!("Error decoding bits: {}", e))?;
+        let break_mode = break_mode.map(|mode| mode.parse::<block::SignetBreak>()).transpose()?;
+
+        let (mut signet_block, challenge) = GenerateBlock::signet(SignetParams {
+            challenge,
+            private_keys,
+            header: Some(HeaderParams {
+                bits,
+                ..Default::default()
+            }),
+            ..Default::default()
+        })
+        .map_err(|e| format!("Error generating signet block: {}", e))?;
+
+        if let Some(mode) = break_mode {
+            signet_block = block::SignetProcessor::process_signet(&signet_block, mode, &challenge)
+                .map_err(|e| format!("Error breaking signet block: {}", e))?;
+        }
+
+        Ok(SignetBlockInfo::new(&signet_block, &challenge))
+    }
+
+    pub fn header_chain(
+        length: Option<usize>,
+        break_mode: Option<String>,
+        index: Option<usize>,
+        prev_blockhash: Option<String>,
+        start_time: Option<u32>,
+        prev_times: Option<String>,
+    ) -> String {
+        let break_mode = match break_mode.map(|mode| mode.parse::<block::ChainBreak>()).transpose() {
+            Ok(mode) => mode,
+            Err(e) => return e,
+        };
+        let prev_blockhash = match prev_blockhash.map(|hash| hash.parse::<BlockHash>()).transpose() {
+            Ok(hash) => hash,
+            Err(e) => return format!("Error decoding previous block hash: {}", e),
+        };
+        let prev_times = match prev_times
+            .map(|times| {
+                times
+                    .split(',')
+                    .map(|time| time.trim().parse::<u32>())
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .transpose()
+        {
+            Ok(times) => times,
+            Err(e) => return format!("Error decoding previous times: {}", e),
+        };
+
+        let chain = match GenerateBlock::header_chain(ChainParams {
+            length,
+            prev_blockhash,
+            prev_times,
+            start_time,
+            ..Default::default()
+        }) {
+            Ok(chain) => chain,
+            Err(e) => return format!("Error generating header chain: {}", e),
+        };
+        let serialize = |headers: &[Header]| {
+            headers
+                .iter()
+                .map(encode::serialize_hex)
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+
+        let mut result = vec![format!("Chain:\n{}", serialize(&chain.headers))];
+        if let Some(mode) = break_mode {
+            match block::ChainProcessor::process_chain(&chain, mode, index) {
+                Ok(broken) => result.push(format!("Broken headers:\n{}", serialize(&broken))),
+                Err(e) => return format!("Error breaking header chain: {}", e),
+            }
+        }
+        result.join("\n---\n")
     }
 
     pub fn transaction(count: u32) -> String {
@@ -101,7 +205,11 @@ impl Generator {
         RegtestManager::new(name_of_wallet, mode_of_cli)
     }
 
-    pub fn break_transaction(transaction: String, cli_flags: Vec<String>) -> String {
+    pub fn break_transaction(
+        transaction: String,
+        cli_flags: Vec<String>,
+        cli_config: Vec<String>,
+    ) -> String {
         // Convert CLI flags to InvalidationFlag HashSet
         let invalidation_flags = Self::parse_cli_flags_to_invalidation_flags(cli_flags);
 
@@ -116,11 +224,18 @@ impl Generator {
             Err(e) => return format!("Error decoding transaction: {}", e),
         };
 
+        let invalidation_config =
+            Self::parse_cli_config_to_invalidation_config(cli_config, invalidation_flags.clone());
+
         // Create invalid version based on specified flags
-        let invalid_tx = transaction::transaction::TransactionInvalidator::invalidate(
+        let invalidated = transaction::transaction::TransactionInvalidator::invalidate_with_config(
             decoded_tx,
-            &invalidation_flags,
+            &invalidation_config,
         );
+        let invalid_tx = match invalidated {
+            Ok(tx) => tx,
+            Err(e) => return format!("Error invalidating transaction: {}", e),
+        };
 
         // Build the result string
         let mut result = String::new();
@@ -160,24 +275,131 @@ impl Generator {
                     transaction::flags::InvalidationFlag::Locktime => {
                         result.push_str("  - Locktime\n")
                     }
+                    transaction::flags::InvalidationFlag::Signature(mode) => {
+                        result.push_str(&format!("  - Signature ({:?})\n", mode))
+                    }
+                    transaction::flags::InvalidationFlag::InputScript(mode) => {
+                        result.push_str(&format!("  - Input Script ({:?})\n", mode))
+                    }
+                    transaction::flags::InvalidationFlag::OutputScript(mode) => {
+                        result.push_str(&format!("  - Output Script ({:?})\n", mode))
+                    }
+                    transaction::flags::InvalidationFlag::Witness(mode) => {
+                        result.push_str(&format!("  - Witness Stack ({:?})\n", mode))
+                    }
+                    transaction::flags::InvalidationFlag::Amount(mode) => {
+                        result.push_str(&format!("  - Output Amount ({:?})\n", mode))
+                    }
+                    transaction::flags::InvalidationFlag::Structure(mode) => {
+                        result.push_str(&format!("  - Transaction Structure ({:?})\n", mode))
+                    }
+                    transaction::flags::InvalidationFlag::Serialization(mode) => {
+                        result.push_str(&format!("  - Serialization ({:?})\n", mode))
+                    }
+                    transaction::flags::InvalidationFlag::Finality(mode) => {
+                        result.push_str(&format!("  - Finality ({:?})\n", mode))
+                    }
+                    transaction::flags::InvalidationFlag::TxVersion(mode) => {
+                        result.push_str(&format!("  - Transaction Version ({:?})\n", mode))
+                    }
                     _ => {}
                 }
             }
         }
 
+        if !invalidation_config.input_indices.is_empty() {
+            result.push_str(&format!(
+                "  - Input Indices: {:?}\n",
+                invalidation_config.input_indices
+            ));
+        }
+        if !invalidation_config.output_indices.is_empty() {
+            result.push_str(&format!(
+                "  - Output Indices: {:?}\n",
+                invalidation_config.output_indices
+            ));
+        }
+
         // Display results
         result.push_str(&format!("\nInputed Transaction:\n{}\n\n", transaction));
         result.push_str(&format!("Invalidated Transaction:\n{:#?}", invalid_tx));
-        result.push_str(&format!(
-            "Invalidated Raw Transaction:\n{:#?}\n\n",
-            encode::serialize_hex(&invalid_tx)
-        ));
+        // Serialization breaks only exist as bytes, so they are applied while encoding, which
+        // also keeps transactions without inputs in the legacy encoding
+        let serialization_breaks: Vec<_> = invalidation_flags
+            .iter()
+            .filter_map(|flag| match flag {
+                transaction::flags::InvalidationFlag::Serialization(mode) => Some(*mode),
+                _ => None,
+            })
+            .collect();
+        let raw_tx = hex::encode(transaction::serialize_broken(&invalid_tx, &serialization_breaks));
+        result.push_str(&format!("Invalidated Raw Transaction:\n{:#?}\n\n", raw_tx));
 
         result
     }
 
+    /// Trace the fields of a serialized transaction or block and, when a field is given,
+    /// mutate its bytes and report how decoding the result fails.
+    pub fn mutate_raw(
+        raw: String,
+        is_block: bool,
+        field: Option<String>,
+        mutation: Option<String>,
+    ) -> String {
+        let bytes = match hex::decode(raw.trim().trim_start_matches("0x")) {
+            Ok(bytes) => bytes,
+            Err(e) => return format!("Error decoding hex: {}", e),
+        };
+        let trace = if is_block {
+            ParseTrace::block(&bytes)
+        } else {
+            ParseTrace::transaction(&bytes)
+        };
+        let trace = match trace {
+            Ok(trace) => trace,
+            Err(e) => return format!("Error tracing fields: {}", e),
+        };
+
+        let mut result = String::from("Parse Trace:\n");
+        for traced in &trace.fields {
+            result.push_str(&format!(
+                "  {} [{}..{}] {}\n",
+                traced.name,
+                traced.start,
+                traced.end,
+                hex::encode(&bytes[traced.start..traced.end])
+            ));
+        }
+
+        let Some(field) = field else {
+            return result;
+        };
+        let mutation = mutation.unwrap_or_else(|| "flip".to_string());
+        let mutation = match mutation.parse::<RawMutation>() {
+            Ok(mutation) => mutation,
+            Err(e) => return e,
+        };
+        let mutated = match RawMutator::mutate(&bytes, &trace, &field, &mutation) {
+            Ok(mutated) => mutated,
+            Err(e) => return format!("Error mutating raw data: {}", e),
+        };
+
+        let decoded = if is_block {
+            encode::deserialize::<bitcoin::Block>(&mutated).map(|_| ())
+        } else {
+            encode::deserialize::<Transaction>(&mutated).map(|_| ())
+        };
+        result.push_str(&format!("\nMutated {} ({:?}):\n", field, mutation));
+        result.push_str(&format!("Mutated Raw (Hex):\n{}\n", hex::encode(&mutated)));
+        match decoded {
+            Ok(()) => result.push_str("Decoding Result: still decodes\n"),
+            Err(e) => result.push_str(&format!("Decoding Result: {}\n", e)),
+        }
+        result
+    }
+
     pub fn break_block(
-        block_header: String,
+        block: String,
         cli_flags: Vec<String>,
         cli_config: Vec<String>,
     ) -> String {
@@ -193,19 +415,27 @@ impl Generator {
         let processing_config =
             Self::parse_cli_config_to_processing_config(cli_config, block_fields);
 
-        // Decode the block header
-        let decoded_header = match Self::decoder_block_header(block_header.clone()) {
-            Ok(header) => header,
-            Err(e) => return format!("Error decoding block header: {}", e),
+        // Decode either a bare header or a full block
+        let is_header_only = block.trim().len() == Header::SIZE * 2;
+        let original_block = if is_header_only {
+            match Self::decoder_block_header(block.trim().to_string()) {
+                Ok(header) => decoder_tools::BlockUtils::create_minimal_block_from_header(header),
+                Err(e) => return format!("Error decoding block header: {}", e),
+            }
+        } else {
+            match decoder_tools::BlockUtils::decode_block_from_hex(block.trim()) {
+                Ok(block) => block,
+                Err(e) => return format!("Error decoding block: {}", e),
+            }
         };
-
-        // Create block from header for processing
-        let original_block =
-            decoder_tools::BlockUtils::create_minimal_block_from_header(decoded_header);
+        let decoded_header = original_block.header;
 
         // Process the block using BlockProcessor
         let processor = block::block::BlockProcessor::new(processing_config.clone());
-        let broken_block = processor.process_block(&original_block);
+        let broken_block = match processor.process_block(&original_block) {
+            Ok(block) => block,
+            Err(e) => return format!("Error breaking block: {}", e),
+        };
 
         // Build the result string
         let mut result = String::new();
@@ -229,6 +459,96 @@ impl Generator {
                     block::block::BlockField::Timestamp => result.push_str("  - Timestamp\n"),
                     block::block::BlockField::Bits => result.push_str("  - Difficulty Bits\n"),
                     block::block::BlockField::Nonce => result.push_str("  - Nonce\n"),
+                    block::block::BlockField::RemoveCoinbase => {
+                        result.push_str("  - Remove Coinbase\n")
+                    }
+                    block::block::BlockField::DuplicateCoinbase => {
+                        result.push_str("  - Duplicate Coinbase\n")
+                    }
+                    block::block::BlockField::ReorderTransactions => {
+                        result.push_str("  - Reorder Transactions\n")
+                    }
+                    block::block::BlockField::CorruptTransaction => {
+                        result.push_str("  - Corrupt Transaction\n")
+                    }
+                    block::block::BlockField::DuplicateTransactions => {
+                        result.push_str("  - Duplicate Transactions (same merkle root)\n")
+                    }
+                    block::block::BlockField::SixtyFourByteTransaction => {
+                        result.push_str("  - 64-byte Transaction\n")
+                    }
+                    block::block::BlockField::MissingWitnessCommitment => {
+                        result.push_str("  - Missing Witness Commitment\n")
+                    }
+                    block::block::BlockField::WrongWitnessCommitment => {
+                        result.push_str("  - Wrong Witness Commitment\n")
+                    }
+                    block::block::BlockField::MisplacedWitnessCommitment => {
+                        result.push_str("  - Misplaced Witness Commitment\n")
+                    }
+                    block::block::BlockField::MultipleWitnessCommitments => {
+                        result.push_str("  - Multiple Witness Commitments\n")
+                    }
+                    block::block::BlockField::WrongWitnessReservedValueSize => {
+                        result.push_str("  - Wrong Witness Reserved Value Size\n")
+                    }
+                    block::block::BlockField::UnexpectedWitness => {
+                        result.push_str("  - Unexpected Witness\n")
+                    }
+                    block::block::BlockField::NonMinimalHeight => {
+                        result.push_str("  - Non-minimal BIP34 Height\n")
+                    }
+                    block::block::BlockField::WrongHeight => {
+                        result.push_str("  - Wrong BIP34 Height\n")
+                    }
+                    block::block::BlockField::MissingHeight => {
+                        result.push_str("  - Missing BIP34 Height\n")
+                    }
+                    block::block::BlockField::ShortCoinbaseScriptSig => {
+                        result.push_str("  - Coinbase ScriptSig Too Short\n")
+                    }
+                    block::block::BlockField::LongCoinbaseScriptSig => {
+                        result.push_str("  - Coinbase ScriptSig Too Long\n")
+                    }
+                    block::block::BlockField::ExcessiveCoinbaseValue => {
+                        result.push_str("  - Coinbase Value Above Subsidy + Fees\n")
+                    }
+                    block::block::BlockField::NonNullCoinbasePrevout => {
+                        result.push_str("  - Non-null Coinbase Prevout\n")
+                    }
+                    block::block::BlockField::ImmatureCoinbaseSpend => {
+                        result.push_str("  - Immature Coinbase Spend\n")
+                    }
+                    block::block::BlockField::HighHash => {
+                        result.push_str("  - Hash Just Above Target\n")
+                    }
+                    block::block::BlockField::BitsAbovePowLimit => {
+                        result.push_str("  - Bits Above Proof of Work Limit\n")
+                    }
+                    block::block::BlockField::NegativeBits => {
+                        result.push_str("  - Negative Bits\n")
+                    }
+                    block::block::BlockField::OverflowBits => {
+                        result.push_str("  - Overflowing Bits\n")
+                    }
+                    block::block::BlockField::ZeroBits => {
+                        result.push_str("  - Zero Target Bits\n")
+                    }
+                    block::block::BlockField::TimeAtMedianTimePast => {
+                        result.push_str("  - Time Equal To Median Time Past\n")
+                    }
+                    block::block::BlockField::TimeBelowMedianTimePast => {
+                        result.push_str("  - Time Below Median Time Past\n")
+                    }
+                    block::block::BlockField::TimeTooNew => {
+                        result.push_str("  - Time Past The Future Limit\n")
+                    }
+                    block::block::BlockField::Timewarp => {
+                        result.push_str("  - Timewarp At Retarget Boundary (BIP94)\n")
+                    }
+                    block::block::BlockField::UnknownDeploymentVersion => {
+                        result.push_str("  - Unknown Deployment Signalled\n")
+                    }
                     _ => {}
                 }
             }
@@ -244,9 +564,42 @@ impl Generator {
                 timestamp_offset
             ));
         }
+        if let Some(hash) = processing_config.prev_blockhash_override {
+            result.push_str(&format!("  - Previous Block Override: {}\n", hash));
+        }
+        if let Some(root) = processing_config.merkle_root_override {
+            result.push_str(&format!("  - Merkle Root Override: {}\n", root));
+        }
+        if let Some(time) = processing_config.time_override {
+            result.push_str(&format!("  - Time Override: {}\n", time));
+        }
+        if let Some(bits) = processing_config.bits_override {
+            result.push_str(&format!("  - Bits Override: 0x{:08x}\n", bits));
+        }
+        if let Some(nonce) = processing_config.nonce_override {
+            result.push_str(&format!("  - Nonce Override: {}\n", nonce));
+        }
+        if !processing_config.prev_blockhash_flips.is_empty() {
+            result.push_str(&format!(
+                "  - Previous Block Bit Flips: {:?}\n",
+                processing_config.prev_blockhash_flips
+            ));
+        }
+        if !processing_config.merkle_root_flips.is_empty() {
+            result.push_str(&format!(
+                "  - Merkle Root Bit Flips: {:?}\n",
+                processing_config.merkle_root_flips
+            ));
+        }
         if !processing_config.randomize_hashes {
             result.push_str("  - Using zero hashes instead of random\n");
         }
+        if let Some(index) = processing_config.corrupt_tx_index {
+            result.push_str(&format!("  - Corrupted Transaction Index: {}\n", index));
+        }
+        if processing_config.recompute_merkle_root {
+            result.push_str("  - Recomputing merkle root and witness commitment\n");
+        }
 
         // Display original header info
         result.push_str("\nOriginal Block Header:\n");
@@ -299,6 +652,18 @@ impl Generator {
             broken_header_hex
         ));
 
+        if !is_header_only {
+            result.push_str(&format!(
+                "\nTransactions: {} -> {}\n",
+                original_block.txdata.len(),
+                broken_block.txdata.len()
+            ));
+            result.push_str(&format!(
+                "\nBroken Block (Hex):\n{}\n",
+                encode::serialize_hex(&broken_block)
+            ));
+        }
+
         result
     }
 
@@ -319,9 +684,15 @@ impl Generator {
                 "--witness" => Some(transaction::flags::InvalidationFlag::WitnessData),
                 "--locktime" => Some(transaction::flags::InvalidationFlag::Locktime),
                 "--all" => Some(transaction::flags::InvalidationFlag::All),
-                _ => {
-                    println!("Warning: Unknown flag '{}' ignored", flag);
-                    None
+                // Flags carrying a mode, such as --sig=high-s
+                other => {
+                    let parsed = other
+                        .strip_prefix("--")
+                        .and_then(transaction::flags::InvalidationFlag::from_str);
+                    if parsed.is_none() {
+                        println!("Warning: Unknown flag '{}' ignored", flag);
+                    }
+                    parsed
                 }
             };
 
@@ -347,6 +718,44 @@ impl Generator {
                 "--bits" => Some(block::block::BlockField::Bits),
                 "--nonce" => Some(block::block::BlockField::Nonce),
                 "--all" => Some(block::block::BlockField::All),
+                "--remove-coinbase" => Some(block::block::BlockField::RemoveCoinbase),
+                "--duplicate-coinbase" => Some(block::block::BlockField::DuplicateCoinbase),
+                "--reorder-txs" => Some(block::block::BlockField::ReorderTransactions),
+                "--corrupt-tx" => Some(block::block::BlockField::CorruptTransaction),
+                "--duplicate-txs" => Some(block::block::BlockField::DuplicateTransactions),
+                "--64-byte-tx" => Some(block::block::BlockField::SixtyFourByteTransaction),
+                "--missing-commitment" => Some(block::block::BlockField::MissingWitnessCommitment),
+                "--wrong-commitment" => Some(block::block::BlockField::WrongWitnessCommitment),
+                "--misplaced-commitment" => {
+                    Some(block::block::BlockField::MisplacedWitnessCommitment)
+                }
+                "--multiple-commitments" => {
+                    Some(block::block::BlockField::MultipleWitnessCommitments)
+                }
+                "--reserved-value-size" => {
+                    Some(block::block::BlockField::WrongWitnessReservedValueSize)
+                }
+                "--unexpected-witness" => Some(block::block::BlockField::UnexpectedWitness),
+                "--non-minimal-height" => Some(block::block::BlockField::NonMinimalHeight),
+                "--wrong-height" => Some(block::block::BlockField::WrongHeight),
+                "--missing-height" => Some(block::block::BlockField::MissingHeight),
+                "--short-coinbase" => Some(block::block::BlockField::ShortCoinbaseScriptSig),
+                "--long-coinbase" => Some(block::block::BlockField::LongCoinbaseScriptSig),
+                "--excessive-coinbase" => Some(block::block::BlockField::ExcessiveCoinbaseValue),
+                "--coinbase-prevout" => Some(block::block::BlockField::NonNullCoinbasePrevout),
+                "--immature-spend" => Some(block::block::BlockField::ImmatureCoinbaseSpend),
+                "--high-hash" => Some(block::block::BlockField::HighHash),
+                "--bits-above-limit" => Some(block::block::BlockField::BitsAbovePowLimit),
+                "--negative-bits" => Some(block::block::BlockField::NegativeBits),
+                "--overflow-bits" => Some(block::block::BlockField::OverflowBits),
+                "--zero-bits" => Some(block::block::BlockField::ZeroBits),
+                "--time-at-mtp" => Some(block::block::BlockField::TimeAtMedianTimePast),
+                "--time-below-mtp" => Some(block::block::BlockField::TimeBelowMedianTimePast),
+                "--time-too-new" => Some(block::block::BlockField::TimeTooNew),
+                "--timewarp" => Some(block::block::BlockField::Timewarp),
+                "--unknown-deployment" => {
+                    Some(block::block::BlockField::UnknownDeploymentVersion)
+                }
                 _ => {
                     println!("Warning: Unknown block field flag '{}' ignored", flag);
                     None
@@ -361,15 +770,74 @@ impl Generator {
         fields
     }
 
+    pub fn parse_cli_config_to_invalidation_config(
+        cli_config: Vec<String>,
+        flags: HashSet<transaction::flags::InvalidationFlag>,
+    ) -> transaction::transaction::InvalidationConfig {
+        let mut config = transaction::transaction::InvalidationConfig {
+            flags,
+            ..Default::default()
+        };
+
+        for config_option in cli_config {
+            if let Some(value_str) = config_option.strip_prefix("--input-index=") {
+                if let Ok(value) = value_str.parse::<usize>() {
+                    config.input_indices.push(value);
+                } else {
+                    println!("Warning: Invalid input index '{}' ignored", value_str);
+                }
+            } else if let Some(value_str) = config_option.strip_prefix("--output-index=") {
+                if let Ok(value) = value_str.parse::<usize>() {
+                    config.output_indices.push(value);
+                } else {
+                    println!("Warning: Invalid output index '{}' ignored", value_str);
+                }
+            } else if let Some(value_str) = config_option.strip_prefix("--prevout-value=") {
+                if let Ok(value) = value_str.parse::<u64>() {
+                    config.prevout_values.push(bitcoin::Amount::from_sat(value));
+                } else {
+                    println!("Warning: Invalid prevout value '{}' ignored", value_str);
+                }
+            } else if let Some(value_str) = config_option.strip_prefix("--tip-height=") {
+                if let Ok(value) = value_str.parse::<u32>() {
+                    config.tip_height = Some(value);
+                } else {
+                    println!("Warning: Invalid tip height '{}' ignored", value_str);
+                }
+            } else if let Some(value_str) = config_option.strip_prefix("--tip-mtp=") {
+                if let Ok(value) = value_str.parse::<u32>() {
+                    config.tip_median_time = Some(value);
+                } else {
+                    println!("Warning: Invalid tip median time past '{}' ignored", value_str);
+                }
+            } else if let Some(value_str) = config_option.strip_prefix("--prevout-confirmations=") {
+                if let Ok(value) = value_str.parse::<u32>() {
+                    config.prevout_confirmations.push(value);
+                } else {
+                    println!("Warning: Invalid prevout confirmations '{}' ignored", value_str);
+                }
+            } else if let Some(value_str) = config_option.strip_prefix("--truc-parent=") {
+                if let Ok(value) = value_str.parse::<OutPoint>() {
+                    config.truc_parent = Some(value);
+                } else {
+                    println!("Warning: Invalid TRUC parent outpoint '{}' ignored", value_str);
+                }
+            } else {
+                println!("Warning: Unknown config option '{}' ignored", config_option);
+            }
+        }
+
+        config
+    }
+
     pub fn parse_cli_config_to_processing_config(
         cli_config: Vec<String>,
         fields: Vec<block::block::BlockField>,
     ) -> block::block::ProcessingConfig {
         let mut config = block::block::ProcessingConfig {
             fields_to_modify: fields,
-            version_override: None,
-            timestamp_offset: None,
             randomize_hashes: true, // default to random hashes
+            ..Default::default()
         };
 
         for config_option in cli_config {
@@ -397,6 +865,96 @@ impl Generator {
                 }
             } else if config_option == "--zero-hashes" {
                 config.randomize_hashes = false;
+            } else if let Some(value_str) = config_option.strip_prefix("--tx-index=") {
+                if let Ok(value) = value_str.parse::<usize>() {
+                    config.corrupt_tx_index = Some(value);
+                } else {
+                    println!("Warning: Invalid transaction index '{}' ignored", value_str);
+                }
+            } else if let Some(value_str) = config_option.strip_prefix("--tx-flag=") {
+                if let Some(flag) = transaction::flags::InvalidationFlag::from_str(value_str) {
+                    config.tx_flags.insert(flag);
+                } else {
+                    println!("Warning: Unknown transaction flag '{}' ignored", value_str);
+                }
+            } else if config_option == "--recompute-merkle-root" {
+                config.recompute_merkle_root = true;
+            } else if let Some(value_str) = config_option.strip_prefix("--coinbase-height=") {
+                if let Ok(value) = value_str.parse::<u32>() {
+                    config.coinbase_height = Some(value);
+                } else {
+                    println!("Warning: Invalid coinbase height '{}' ignored", value_str);
+                }
+            } else if let Some(value_str) = config_option.strip_prefix("--coinbase-fees=") {
+                if let Ok(value) = value_str.parse::<u64>() {
+                    config.coinbase_fees = Some(value);
+                } else {
+                    println!("Warning: Invalid coinbase fees '{}' ignored", value_str);
+                }
+            } else if let Some(value_str) = config_option.strip_prefix("--network=") {
+                if let Ok(value) = value_str.parse::<bitcoin::Network>() {
+                    config.network = Some(value);
+                } else {
+                    println!("Warning: Unknown network '{}' ignored", value_str);
+                }
+            } else if let Some(value_str) = config_option.strip_prefix("--spend-coinbase=") {
+                if let Ok(value) = value_str.parse::<OutPoint>() {
+                    config.immature_coinbase = Some(value);
+                } else {
+                    println!("Warning: Invalid coinbase outpoint '{}' ignored", value_str);
+                }
+            } else if let Some(value_str) = config_option.strip_prefix("--prev-hash-override=") {
+                if let Ok(value) = value_str.parse::<bitcoin::BlockHash>() {
+                    config.prev_blockhash_override = Some(value);
+                } else {
+                    println!("Warning: Invalid previous block hash '{}' ignored", value_str);
+                }
+            } else if let Some(value_str) = config_option.strip_prefix("--merkle-root-override=") {
+                if let Ok(value) = value_str.parse::<bitcoin::TxMerkleNode>() {
+                    config.merkle_root_override = Some(value);
+                } else {
+                    println!("Warning: Invalid merkle root '{}' ignored", value_str);
+                }
+            } else if let Some(value_str) = config_option.strip_prefix("--time-override=") {
+                if let Ok(value) = value_str.parse::<u32>() {
+                    config.time_override = Some(value);
+                } else {
+                    println!("Warning: Invalid time override '{}' ignored", value_str);
+                }
+            } else if let Some(value_str) = config_option.strip_prefix("--bits-override=") {
+                let hex = value_str.trim_start_matches("0x");
+                if let Ok(value) = u32::from_str_radix(hex, 16) {
+                    config.bits_override = Some(value);
+                } else {
+                    println!("Warning: Invalid bits override '{}' ignored", value_str);
+                }
+            } else if let Some(value_str) = config_option.strip_prefix("--nonce-override=") {
+                if let Ok(value) = value_str.parse::<u32>() {
+                    config.nonce_override = Some(value);
+                } else {
+                    println!("Warning: Invalid nonce override '{}' ignored", value_str);
+                }
+            } else if let Some(value_str) = config_option.strip_prefix("--prev-times=") {
+                match value_str.split(',').map(|time| time.trim().parse::<u32>()).collect() {
+                    Ok(times) => config.prev_times = times,
+                    Err(_) => println!("Warning: Invalid previous times '{}' ignored", value_str),
+                }
+            } else if let Some(value_str) = config_option.strip_prefix("--adjusted-time=") {
+                if let Ok(value) = value_str.parse::<u32>() {
+                    config.adjusted_time = Some(value);
+                } else {
+                    println!("Warning: Invalid adjusted time '{}' ignored", value_str);
+                }
+            } else if let Some(value_str) = config_option.strip_prefix("--flip-prev-hash=") {
+                match Self::parse_bit_positions(value_str) {
+                    Some(positions) => config.prev_blockhash_flips.extend(positions),
+                    None => println!("Warning: Invalid bit positions '{}' ignored", value_str),
+                }
+            } else if let Some(value_str) = config_option.strip_prefix("--flip-merkle-root=") {
+                match Self::parse_bit_positions(value_str) {
+                    Some(positions) => config.merkle_root_flips.extend(positions),
+                    None => println!("Warning: Invalid bit positions '{}' ignored", value_str),
+                }
             } else {
                 println!("Warning: Unknown config option '{}' ignored", config_option);
             }
@@ -404,4 +962,12 @@ impl Generator {
 
         config
     }
+
+    /// Comma separated bit positions of a 256-bit hash, each below 256.
+    fn parse_bit_positions(value: &str) -> Option<Vec<usize>> {
+        value
+            .split(',')
+            .map(|position| position.trim().parse::<usize>().ok().filter(|bit| *bit < 256))
+            .collect()
+    }
 }
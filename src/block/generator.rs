@@ -1,24 +1,37 @@
-use bitcoin::Block;
+use std::error::Error;
+
+use bitcoin::{block::Header, Block, ScriptBuf};
 use super::random::block::{BlockParams, RandomBlock};
+use super::random::budget::{BlockFiller, BudgetParams};
+use super::random::chain::{ChainParams, HeaderChain, RandomChain};
+use super::signet::{Signet, SignetParams};
 use bitcoin::OutPoint;
 
 use crate::transaction::{
     generator::GenerateTx,
     random::{
+        coinbase::{witness_commitment_index, witness_commitment_script},
         input::InputParams,
         transaction::{TxParams},
     },
 };
 
+/// Hashes tried by `GenerateBlock::mine` before giving up.
+pub const MINE_MAX_ATTEMPTS: u64 = 1 << 24;
+
 pub struct GenerateBlock {}
 
 impl GenerateBlock {
     pub fn valid_random(mut params: BlockParams) -> Block {
-        let mut input_params = InputParams::default();
-        input_params.outpoint = Some(OutPoint::null());
+        let input_params = InputParams {
+            outpoint: Some(OutPoint::null()),
+            ..Default::default()
+        };
 
-        let mut coinbase_params = TxParams::default();
-        coinbase_params.input = Some(input_params);
+        let coinbase_params = TxParams {
+            input: Some(input_params),
+            ..Default::default()
+        };
 
         let coinbase_info = GenerateTx::valid_random(coinbase_params);
 
@@ -28,4 +41,66 @@ impl GenerateBlock {
 
         Block::random(params)
     }
-}
\ No newline at end of file
+
+    /// Generate a block filled up to an exact weight and/or sigop cost.
+    pub fn with_budget(params: BudgetParams) -> Result<Block, Box<dyn Error>> {
+        BlockFiller::fill(params)
+    }
+
+    /// Generate a signed and mined signet block, returned along with its challenge.
+    pub fn signet(params: SignetParams) -> Result<(Block, ScriptBuf), Box<dyn Error>> {
+        Signet::generate(params)
+    }
+
+    /// Refresh the coinbase witness commitment, when there is one, and the header merkle root.
+    pub fn recommit(block: &mut Block) {
+        if let Some(coinbase) = block.txdata.first() {
+            let reserved_value = coinbase.input.first().and_then(|input| input.witness.nth(0));
+
+            if let (Some(index), Some(reserved_value), Some(witness_root)) = (
+                witness_commitment_index(coinbase),
+                reserved_value,
+                block.witness_root(),
+            ) {
+                let commitment = Block::compute_witness_commitment(&witness_root, reserved_value);
+                block.txdata[0].output[index].script_pubkey =
+                    witness_commitment_script(&commitment);
+            }
+        }
+
+        if let Some(merkle_root) = block.compute_merkle_root() {
+            block.header.merkle_root = merkle_root;
+        }
+    }
+
+    /// Generate a chain of linked headers with valid proof of work and timestamps.
+    pub fn header_chain(params: ChainParams) -> Result<HeaderChain, Box<dyn Error>> {
+        HeaderChain::random(params)
+    }
+
+    /// Grind the nonce until the header hash meets the target encoded in its bits.
+    ///
+    /// Only practical for easy targets such as regtest's; the time field is bumped whenever
+    /// the nonce space is exhausted. Fails after `MINE_MAX_ATTEMPTS` hashes.
+    pub fn mine(mut header: Header) -> Result<Header, Box<dyn Error>> {
+        let target = header.target();
+
+        for _ in 0..MINE_MAX_ATTEMPTS {
+            if target.is_met_by(header.block_hash()) {
+                return Ok(header);
+            }
+
+            header.nonce = header.nonce.wrapping_add(1);
+            if header.nonce == 0 {
+                header.time = header.time.wrapping_add(1);
+            }
+        }
+
+        Err(format!(
+            "No hash meeting the target of bits {:#010x} within {} attempts",
+            header.bits.to_consensus(),
+            MINE_MAX_ATTEMPTS
+        )
+        .into())
+    }
+}
@@ -0,0 +1,91 @@
+use std::fmt;
+
+use bitcoin::{block::Header, consensus::encode, consensus::encode::VarInt, Block, ScriptBuf};
+use serde::Serialize;
+
+use super::random::budget::BlockFiller;
+use super::signet::Signet;
+
+/// Serialized form of a block along with its size figures and transaction ids.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BlockInfo {
+    /// Full consensus serialization, as accepted by `submitblock`.
+    pub hex: String,
+    pub header: String,
+    pub hash: String,
+    /// Size without witness data.
+    pub stripped_size: usize,
+    pub size: usize,
+    pub weight: u64,
+    /// Sigop cost of the scriptSigs and scriptPubKeys, see `BlockFiller::legacy_sigop_cost`.
+    pub sigop_cost: u64,
+    pub txids: Vec<String>,
+    pub wtxids: Vec<String>,
+}
+
+impl BlockInfo {
+    /// Size of the block serialized without witness data.
+    pub fn stripped_size(block: &Block) -> usize {
+        Header::SIZE
+            + VarInt::from(block.txdata.len()).size()
+            + block.txdata.iter().map(|tx| tx.base_size()).sum::<usize>()
+    }
+}
+
+impl From<&Block> for BlockInfo {
+    fn from(block: &Block) -> Self {
+        BlockInfo {
+            hex: encode::serialize_hex(block),
+            header: encode::serialize_hex(&block.header),
+            hash: block.block_hash().to_string(),
+            stripped_size: Self::stripped_size(block),
+            size: block.total_size(),
+            weight: block.weight().to_wu(),
+            sigop_cost: BlockFiller::legacy_sigop_cost(&block.txdata),
+            txids: block.txdata.iter().map(|tx| tx.compute_txid().to_string()).collect(),
+            wtxids: block.txdata.iter().map(|tx| tx.compute_wtxid().to_string()).collect(),
+        }
+    }
+}
+
+impl fmt::Display for BlockInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Block hash: {}", self.hash)?;
+        writeln!(f, "Size: {} bytes", self.size)?;
+        writeln!(f, "Stripped size: {} bytes", self.stripped_size)?;
+        writeln!(f, "Weight: {} WU", self.weight)?;
+        writeln!(f, "Sigop cost: {}", self.sigop_cost)?;
+        writeln!(f, "TxIDs: {:#?}", self.txids)?;
+        writeln!(f, "WTxIDs: {:#?}", self.wtxids)?;
+        writeln!(f, "Block Header encoded: {}", self.header)?;
+        write!(f, "Raw block: {}", self.hex)
+    }
+}
+
+/// A signet block along with the challenge it was signed for and its solution.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SignetBlockInfo {
+    pub challenge: String,
+    /// Raw solution, empty when the block carries none.
+    pub solution: String,
+    #[serde(flatten)]
+    pub block: BlockInfo,
+}
+
+impl SignetBlockInfo {
+    pub fn new(block: &Block, challenge: &ScriptBuf) -> Self {
+        SignetBlockInfo {
+            challenge: challenge.to_hex_string(),
+            solution: Signet::solution(block).map(hex::encode).unwrap_or_default(),
+            block: BlockInfo::from(block),
+        }
+    }
+}
+
+impl fmt::Display for SignetBlockInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Challenge: {}", self.challenge)?;
+        writeln!(f, "Signet solution: {}", self.solution)?;
+        write!(f, "{}", self.block)
+    }
+}
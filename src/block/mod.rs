@@ -1,2 +1,4 @@
 pub mod random;
 pub mod generator;
+pub mod info;
+pub mod signet;
@@ -36,8 +36,10 @@ impl RandomBlock for Block {
         });
 
         let header = params.header.unwrap_or_else(|| {
-            let mut header_params = HeaderParams::default();
-            header_params.txs = Some(tx_data.clone());
+            let header_params = HeaderParams {
+                txs: Some(tx_data.clone()),
+                ..Default::default()
+            };
 
             Header::random(header_params)
         });
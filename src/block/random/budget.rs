@@ -0,0 +1,206 @@
+use std::error::Error;
+
+use bitcoin::{
+    absolute::LockTime,
+    block::Header,
+    consensus::encode::VarInt,
+    hashes::Hash,
+    opcodes::all::{OP_CHECKSIG, OP_RETURN},
+    params::Params,
+    transaction::Version,
+    Amount, Block, OutPoint, ScriptBuf, Sequence, Transaction, TxIn, TxMerkleNode, TxOut, Txid,
+    Witness,
+};
+use rand::Rng;
+
+use crate::block::generator::GenerateBlock;
+use crate::transaction::{generator::GenerateTx, random::coinbase::CoinbaseParams};
+
+use super::header::{HeaderParams, RandomHeader};
+
+/// Consensus limit on block weight (BIP141).
+pub const MAX_BLOCK_WEIGHT: u64 = 4_000_000;
+
+/// Consensus limit on the sigop cost of a block (BIP141).
+pub const MAX_BLOCK_SIGOPS_COST: u64 = 80_000;
+
+/// Legacy sigops are counted four times towards the sigop cost.
+pub const WITNESS_SCALE_FACTOR: u64 = 4;
+
+/// Weight of each bulk filler transaction, the standard transaction weight limit.
+const FILLER_CHUNK_WEIGHT: u64 = 400_000;
+
+/// Sigops placed in a single filler transaction, the standard per-transaction limit.
+const SIGOPS_PER_TX: u64 = 4_000;
+
+#[derive(Default)]
+pub struct BudgetParams {
+    /// Exact block weight to reach, e.g. `MAX_BLOCK_WEIGHT` or `MAX_BLOCK_WEIGHT + 1`.
+    pub weight: Option<u64>,
+    /// Exact legacy sigop cost to reach, must be a multiple of `WITNESS_SCALE_FACTOR`.
+    ///
+    /// Witness sigops, the only ones costing 1, are counted against the spent outputs, which
+    /// the filler transactions don't have. `MAX_BLOCK_SIGOPS_COST + 4` is therefore the closest
+    /// cost above the limit.
+    pub sigops: Option<u64>,
+    pub coinbase: Option<CoinbaseParams>,
+    /// Header fields to keep, bits default to the regtest proof of work limit.
+    pub header: Option<HeaderParams>,
+}
+
+pub struct BlockFiller {}
+
+impl BlockFiller {
+    /// Build a block whose weight and/or sigop cost land exactly on the requested budget.
+    ///
+    /// Sigops are placed in bare `OP_CHECKSIG` outputs, weight is padded with `OP_RETURN`
+    /// outputs and finally tuned one weight unit at a time through witness bytes. The header
+    /// is mined last, so nodes reach the weight and sigop checks.
+    pub fn fill(params: BudgetParams) -> Result<Block, Box<dyn Error>> {
+        let mut coinbase_params = params.coinbase.unwrap_or_default();
+        coinbase_params.witness_commitment = Some(true);
+
+        let coinbase = GenerateTx::coinbase(coinbase_params);
+        let mut txs = vec![coinbase];
+
+        if let Some(sigops) = params.sigops {
+            if sigops % WITNESS_SCALE_FACTOR != 0 {
+                return Err(format!(
+                    "Sigop cost {} is not a multiple of {}",
+                    sigops, WITNESS_SCALE_FACTOR
+                )
+                .into());
+            }
+
+            let used = Self::legacy_sigop_cost(&txs);
+            let mut remaining = sigops
+                .checked_sub(used)
+                .ok_or_else(|| format!("Coinbase alone already costs {} sigops", used))?
+                / WITNESS_SCALE_FACTOR;
+
+            while remaining > 0 {
+                let count = remaining.min(SIGOPS_PER_TX);
+                txs.push(Self::sigop_tx(count));
+                remaining -= count;
+            }
+        }
+
+        if let Some(weight) = params.weight {
+            let min_filler = Self::filler_tx(0).weight().to_wu();
+
+            loop {
+                let used = Self::block_weight(&txs, txs.len() + 1);
+                let remaining = weight.saturating_sub(used);
+
+                if remaining < min_filler {
+                    return Err(format!(
+                        "Weight {} cannot be reached exactly, the block already weighs {}",
+                        weight,
+                        Self::block_weight(&txs, txs.len())
+                    )
+                    .into());
+                }
+
+                if remaining > FILLER_CHUNK_WEIGHT + 2 * min_filler {
+                    txs.push(Self::filler_tx(FILLER_CHUNK_WEIGHT));
+                } else {
+                    txs.push(Self::filler_tx(remaining));
+                    break;
+                }
+            }
+        }
+
+        // The merkle root is recomputed once the transactions are final.
+        let mut header_params = params.header.unwrap_or_default();
+        header_params.merkle_root.get_or_insert_with(TxMerkleNode::all_zeros);
+        header_params
+            .bits
+            .get_or_insert_with(|| Params::REGTEST.max_attainable_target.to_compact_lossy());
+
+        let mut block = Block {
+            header: Header::random(header_params),
+            txdata: txs,
+        };
+        GenerateBlock::recommit(&mut block);
+        block.header = GenerateBlock::mine(block.header)?;
+
+        Ok(block)
+    }
+
+    /// Sigop cost of the scriptSigs and scriptPubKeys, as counted without the UTXO set.
+    pub fn legacy_sigop_cost(txs: &[Transaction]) -> u64 {
+        txs.iter()
+            .map(|tx| {
+                let inputs: usize = tx
+                    .input
+                    .iter()
+                    .map(|input| input.script_sig.count_sigops_legacy())
+                    .sum();
+                let outputs: usize = tx
+                    .output
+                    .iter()
+                    .map(|output| output.script_pubkey.count_sigops_legacy())
+                    .sum();
+                (inputs + outputs) as u64 * WITNESS_SCALE_FACTOR
+            })
+            .sum()
+    }
+
+    /// Weight of a block holding `txs` once it contains `tx_count` transactions.
+    fn block_weight(txs: &[Transaction], tx_count: usize) -> u64 {
+        let overhead = (Header::SIZE + VarInt::from(tx_count).size()) as u64 * WITNESS_SCALE_FACTOR;
+        overhead + txs.iter().map(|tx| tx.weight().to_wu()).sum::<u64>()
+    }
+
+    /// A transaction with a single output made of `count` bare `OP_CHECKSIG`s.
+    fn sigop_tx(count: u64) -> Transaction {
+        let script = vec![OP_CHECKSIG.to_u8(); count as usize];
+        Self::skeleton(ScriptBuf::from_bytes(script), Witness::default())
+    }
+
+    /// A transaction weighing exactly `weight`, or the smallest filler if `weight` is lower.
+    fn filler_tx(weight: u64) -> Transaction {
+        let mut tx = Self::skeleton(
+            ScriptBuf::from_bytes(vec![OP_RETURN.to_u8()]),
+            Witness::from_slice(&[Vec::<u8>::new()]),
+        );
+        let min_weight = tx.weight().to_wu();
+        if weight <= min_weight {
+            return tx;
+        }
+
+        // Spend the bulk on non-witness bytes, keeping some slack for the script length varint
+        // growing; padding with OP_0 keeps the output free of sigops.
+        let padding = (weight - min_weight).saturating_sub(16) / WITNESS_SCALE_FACTOR;
+        let mut script = vec![OP_RETURN.to_u8()];
+        script.resize(1 + padding as usize, 0x00);
+        tx.output[0].script_pubkey = ScriptBuf::from_bytes(script);
+
+        // The slack is below 253 bytes, so the witness item length stays a single byte.
+        let slack = weight - tx.weight().to_wu();
+        tx.input[0].witness = Witness::from_slice(&[vec![0u8; slack as usize]]);
+
+        debug_assert_eq!(tx.weight().to_wu(), weight);
+        tx
+    }
+
+    fn skeleton(script_pubkey: ScriptBuf, witness: Witness) -> Transaction {
+        Transaction {
+            version: Version::TWO,
+            lock_time: LockTime::ZERO,
+            input: vec![TxIn {
+                previous_output: OutPoint {
+                    txid: Txid::from_byte_array(rand::rng().random()),
+                    vout: 0,
+                },
+                script_sig: ScriptBuf::new(),
+                sequence: Sequence::MAX,
+                witness,
+            }],
+            output: vec![TxOut {
+                value: Amount::ZERO,
+                script_pubkey,
+            }],
+        }
+    }
+}
@@ -0,0 +1,105 @@
+use std::error::Error;
+
+use bitcoin::{
+    block::{Header, Version},
+    hashes::Hash,
+    params::Params,
+    BlockHash, CompactTarget, TxMerkleNode,
+};
+
+use rand::Rng;
+
+use crate::block::generator::GenerateBlock;
+
+use super::timestamp::{Timestamp, MEDIAN_TIME_SPAN};
+
+/// Target spacing used to lay out timestamps of a generated chain.
+pub const BLOCK_SPACING: u32 = 600;
+
+#[derive(Default)]
+pub struct ChainParams {
+    pub length: Option<usize>,
+    pub prev_blockhash: Option<BlockHash>,
+    /// Timestamps of the blocks preceding the chain, oldest first.
+    pub prev_times: Option<Vec<u32>>,
+    pub start_time: Option<u32>,
+    pub version: Option<Version>,
+    /// Version of each header in order, falling back to `version` past its end.
+    pub versions: Option<Vec<Version>>,
+    pub bits: Option<CompactTarget>,
+}
+
+/// An ordered list of headers where every header commits to the previous one.
+#[derive(Debug, Clone)]
+pub struct HeaderChain {
+    pub headers: Vec<Header>,
+    /// Timestamps of the ancestors the chain was built on, oldest first.
+    pub prev_times: Vec<u32>,
+}
+
+impl HeaderChain {
+    pub fn tip(&self) -> Option<&Header> {
+        self.headers.last()
+    }
+
+    /// Timestamps of the ancestors followed by the chain itself, oldest first.
+    pub fn times(&self) -> Vec<u32> {
+        let mut times = self.prev_times.clone();
+        times.extend(self.headers.iter().map(|header| header.time));
+        times
+    }
+
+    /// Median time past of the tip, the bound the next header has to exceed.
+    pub fn median_time_past(&self) -> Option<u32> {
+        Timestamp::median_time_past(&self.times())
+    }
+}
+
+pub trait RandomChain {
+    fn random(params: ChainParams) -> Result<HeaderChain, Box<dyn Error>>;
+}
+
+impl RandomChain for HeaderChain {
+    fn random(params: ChainParams) -> Result<HeaderChain, Box<dyn Error>> {
+        let length = params.length.unwrap_or(MEDIAN_TIME_SPAN);
+        let prev_times = params.prev_times.unwrap_or_default();
+        let version = params.version.unwrap_or(Version::NO_SOFT_FORK_SIGNALLING);
+        let versions = params.versions.unwrap_or_default();
+        let bits = params
+            .bits
+            .unwrap_or_else(|| Params::REGTEST.max_attainable_target.to_compact_lossy());
+
+        // Lay the chain out so the tip lands on the current time.
+        let start_time = params.start_time.unwrap_or_else(|| {
+            Timestamp::now().saturating_sub(BLOCK_SPACING.saturating_mul(length as u32))
+        });
+
+        let mut chain = HeaderChain {
+            headers: Vec::with_capacity(length),
+            prev_times,
+        };
+        let mut prev_blockhash = params.prev_blockhash.unwrap_or_else(BlockHash::all_zeros);
+
+        for height in 0..length {
+            let scheduled = start_time.saturating_add(BLOCK_SPACING.saturating_mul(height as u32));
+            let time = match chain.median_time_past() {
+                Some(median) => scheduled.max(median.saturating_add(1)),
+                None => scheduled,
+            };
+
+            let header = GenerateBlock::mine(Header {
+                version: versions.get(height).copied().unwrap_or(version),
+                prev_blockhash,
+                merkle_root: TxMerkleNode::from_byte_array(rand::rng().random()),
+                time,
+                bits,
+                nonce: 0,
+            })?;
+
+            prev_blockhash = header.block_hash();
+            chain.headers.push(header);
+        }
+
+        Ok(chain)
+    }
+}
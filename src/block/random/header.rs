@@ -9,6 +9,7 @@ use super::{
     bits::RandomBits,
     block::{BlockParams, RandomBlock},
     merkle_root::{MerkleRoot, MerkleRootParams},
+    timestamp::{Timestamp, TimestampParams},
     version::RandomVersion,
 };
 
@@ -21,6 +22,8 @@ pub struct HeaderParams {
     pub bits: Option<CompactTarget>,
     pub nonce: Option<u32>,
     pub txs: Option<Vec<Transaction>>,
+    /// Used when `time` is not given, panics if its valid window is empty.
+    pub timestamp: Option<TimestampParams>,
 }
 
 
@@ -33,20 +36,25 @@ impl RandomHeader for Header {
         Header {
             version: params.version.unwrap_or_else(Version::random),
             prev_blockhash: params.prev_blockhash.unwrap_or_else(|| {
-                let mut h_params = HeaderParams::default();
-                h_params.prev_blockhash = Some(BlockHash::all_zeros());
+                let h_params = HeaderParams {
+                    prev_blockhash: Some(BlockHash::all_zeros()),
+                    ..Default::default()
+                };
 
-                let mut block_params = BlockParams::default();
-                block_params.header = Some(Header::random(h_params));
+                let block_params = BlockParams {
+                    header: Some(Header::random(h_params)),
+                    ..Default::default()
+                };
 
                 Block::random(block_params).block_hash()
             }),
             merkle_root: params
                 .merkle_root
                 .unwrap_or_else(|| TxMerkleNode::random(MerkleRootParams { txs: params.txs })),
-            time: params
-                .time
-                .unwrap_or_else(|| rand::thread_rng().gen::<u32>()),
+            time: params.time.unwrap_or_else(|| {
+                Timestamp::random(params.timestamp.unwrap_or_default())
+                    .expect("no valid timestamp for the given ancestors")
+            }),
             bits: params.bits.unwrap_or_else(CompactTarget::random),
             nonce: params
                 .nonce
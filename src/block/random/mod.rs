@@ -1,5 +1,8 @@
 pub mod bits;
 pub mod block;
+pub mod budget;
+pub mod chain;
 pub mod merkle_root;
+pub mod timestamp;
 pub mod version;
 pub mod header;
\ No newline at end of file
@@ -0,0 +1,82 @@
+use std::error::Error;
+
+use rand::Rng;
+
+/// Number of previous blocks used to compute the median time past.
+pub const MEDIAN_TIME_SPAN: usize = 11;
+
+/// How far ahead of the network-adjusted time a block timestamp may be.
+pub const MAX_FUTURE_BLOCK_TIME: u32 = 2 * 60 * 60;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TimestampMode {
+    /// Strictly above the median time past and not beyond the future limit.
+    #[default]
+    Valid,
+    /// Exactly the median time past, rejected as `time-too-old`.
+    MedianTimePast,
+    /// Exactly the adjusted time plus two hours, the latest accepted value.
+    MaxFuture,
+}
+
+#[derive(Default)]
+pub struct TimestampParams {
+    /// Timestamps of the previous blocks, oldest first.
+    pub prev_times: Option<Vec<u32>>,
+    pub adjusted_time: Option<u32>,
+    pub mode: Option<TimestampMode>,
+}
+
+pub struct Timestamp {}
+
+impl Timestamp {
+    /// Median of the last `MEDIAN_TIME_SPAN` timestamps, as computed by `GetMedianTimePast`.
+    pub fn median_time_past(prev_times: &[u32]) -> Option<u32> {
+        if prev_times.is_empty() {
+            return None;
+        }
+
+        let start = prev_times.len().saturating_sub(MEDIAN_TIME_SPAN);
+        let mut window = prev_times[start..].to_vec();
+        window.sort_unstable();
+
+        Some(window[window.len() / 2])
+    }
+
+    /// Current system time, used when no adjusted network time is supplied.
+    pub fn now() -> u32 {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as u32
+    }
+
+    /// Fails in `Valid` mode when the chain is so far ahead of the clock that no timestamp is
+    /// both above the median time past and within the future limit.
+    pub fn random(params: TimestampParams) -> Result<u32, Box<dyn Error>> {
+        let prev_times = params.prev_times.unwrap_or_default();
+        let adjusted_time = params.adjusted_time.unwrap_or_else(Self::now);
+        let max_time = adjusted_time.saturating_add(MAX_FUTURE_BLOCK_TIME);
+
+        // Without ancestors anything past the future limit is the only constraint, so stay
+        // within the same window around the adjusted time.
+        let median = Self::median_time_past(&prev_times)
+            .unwrap_or_else(|| adjusted_time.saturating_sub(MAX_FUTURE_BLOCK_TIME));
+
+        match params.mode.unwrap_or_default() {
+            TimestampMode::Valid => {
+                let min_time = median.saturating_add(1);
+                if min_time > max_time {
+                    return Err(format!(
+                        "No valid timestamp, the median time past {} is beyond the limit {}",
+                        median, max_time
+                    )
+                    .into());
+                }
+                Ok(rand::rng().random_range(min_time..=max_time))
+            }
+            TimestampMode::MedianTimePast => Ok(median),
+            TimestampMode::MaxFuture => Ok(max_time),
+        }
+    }
+}
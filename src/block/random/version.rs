@@ -1,6 +1,17 @@
-use bitcoin::block::Version;
+use std::error::Error;
+
+use bitcoin::{block::Version, params::Params, Network};
 use secp256k1::rand::{self, Rng};
 
+/// Top bits a BIP9 signalling version must carry.
+pub const VERSIONBITS_TOP_BITS: i32 = 0x20000000;
+
+/// Mask selecting the BIP9 top bits of a version.
+pub const VERSIONBITS_TOP_MASK: i32 = 0xE0000000u32 as i32;
+
+/// Number of bits available for parallel deployments (BIP9).
+pub const VERSIONBITS_NUM_BITS: u8 = 29;
+
 pub trait RandomVersion {
     fn random() -> Version;
 }
@@ -19,3 +30,123 @@ impl RandomVersion for Version {
         Version::from_consensus(rand::thread_rng().gen::<i32>())
     }
 }
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum VersionMode {
+    /// BIP9 top bits with the requested deployment bits set.
+    #[default]
+    Signal,
+    /// Lowest version accepted at the height, following BIP34, BIP66 and BIP65.
+    Minimum,
+    /// A version below the minimum required at the height, rejected as `bad-version`.
+    ///
+    /// Fails below the BIP34 height, where every version is accepted.
+    Outdated,
+}
+
+#[derive(Default)]
+pub struct VersionParams {
+    pub mode: Option<VersionMode>,
+    /// Deployment bits to signal, a random one when not given.
+    pub deployments: Option<Vec<u8>>,
+    /// Height of the block, past every activation height when not given.
+    pub height: Option<u32>,
+    pub network: Option<Network>,
+}
+
+pub struct VersionBits {}
+
+impl VersionBits {
+    pub fn random(params: VersionParams) -> Result<Version, Box<dyn Error>> {
+        let network = params.network.unwrap_or(Network::Regtest);
+        let height = params.height.unwrap_or(u32::MAX);
+
+        match params.mode.unwrap_or_default() {
+            VersionMode::Signal => {
+                let deployments = params.deployments.unwrap_or_else(|| {
+                    vec![rand::thread_rng().gen_range(0..VERSIONBITS_NUM_BITS)]
+                });
+                Ok(Self::signalling(&deployments))
+            }
+            VersionMode::Minimum => Ok(Version::from_consensus(Self::minimum(height, network))),
+            VersionMode::Outdated => {
+                let outdated = Self::outdated(height, network).ok_or_else(|| {
+                    format!("Every version is accepted at height {} on {}", height, network)
+                })?;
+                Ok(Version::from_consensus(rand::thread_rng().gen_range(1..=outdated)))
+            }
+        }
+    }
+
+    /// BIP9 version signalling every bit in `deployments`, bits above 28 are ignored.
+    pub fn signalling(deployments: &[u8]) -> Version {
+        let bits = deployments
+            .iter()
+            .filter(|bit| **bit < VERSIONBITS_NUM_BITS)
+            .fold(0, |bits, bit| bits | (1 << bit));
+
+        Version::from_consensus(VERSIONBITS_TOP_BITS | bits)
+    }
+
+    /// Whether `version` carries the BIP9 top bits, so its deployment bits are counted.
+    pub fn has_top_bits(version: Version) -> bool {
+        version.to_consensus() & VERSIONBITS_TOP_MASK == VERSIONBITS_TOP_BITS
+    }
+
+    /// Lowest version a block at `height` must have on `network`.
+    pub fn minimum(height: u32, network: Network) -> i32 {
+        let (bip34_height, bip66_height, bip65_height) = Self::buried_heights(network);
+
+        if height >= bip65_height {
+            4
+        } else if height >= bip66_height {
+            3
+        } else if height >= bip34_height {
+            2
+        } else {
+            1
+        }
+    }
+
+    /// Highest version rejected as `bad-version` at `height` on `network`, `None` before BIP34
+    /// where every version is accepted.
+    pub fn outdated(height: u32, network: Network) -> Option<i32> {
+        let minimum = Self::minimum(height, network);
+        (minimum > 1).then_some(minimum - 1)
+    }
+
+    /// Activation heights of BIP34, BIP66 and BIP65 on `network`.
+    ///
+    /// Regtest follows Bitcoin Core, where all three are active from height 1, rather than the
+    /// older heights of `Params::REGTEST`.
+    pub fn buried_heights(network: Network) -> (u32, u32, u32) {
+        if network == Network::Regtest {
+            return (1, 1, 1);
+        }
+
+        let params = Params::new(network);
+        (params.bip34_height, params.bip66_height, params.bip65_height)
+    }
+
+    /// Versions of consecutive retarget periods, each signalling `bit` in its first
+    /// `signalling[n]` blocks.
+    ///
+    /// A period with at least `rule_change_activation_threshold` signals moves a STARTED
+    /// deployment to LOCKED_IN, one more period makes it ACTIVE.
+    pub fn schedule(bit: u8, signalling: &[u32], network: Network) -> Vec<Version> {
+        let window = Params::new(network).miner_confirmation_window;
+
+        signalling
+            .iter()
+            .flat_map(|&count| {
+                (0..window).map(move |n| {
+                    if n < count {
+                        Self::signalling(&[bit])
+                    } else {
+                        Version::NO_SOFT_FORK_SIGNALLING
+                    }
+                })
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,351 @@
+use std::error::Error;
+
+use bitcoin::{
+    absolute::LockTime,
+    block::Header,
+    consensus::{deserialize_partial, encode, Encodable},
+    ecdsa,
+    hashes::Hash,
+    key::{Keypair, TapTweak},
+    merkle_tree,
+    opcodes::all::{OP_CHECKMULTISIG, OP_RETURN},
+    params::Params,
+    script::{Builder, Instruction, PushBytesBuf},
+    secp256k1::{Message, Secp256k1},
+    sighash::{EcdsaSighashType, Prevouts, SighashCache, TapSighashType},
+    taproot,
+    transaction::Version,
+    Amount, Block, OutPoint, PrivateKey, PublicKey, Script, ScriptBuf, Sequence, Transaction, TxIn,
+    TxMerkleNode, TxOut, Witness,
+};
+
+use crate::transaction::{
+    generator::GenerateTx,
+    random::coinbase::{witness_commitment_index, CoinbaseParams},
+};
+
+use super::generator::GenerateBlock;
+use super::random::header::{HeaderParams, RandomHeader};
+
+/// Prefix of the signet solution pushed after the witness commitment (BIP325).
+pub const SIGNET_HEADER: [u8; 4] = [0xec, 0xc7, 0xda, 0xa2];
+
+/// Size of the witness commitment push, `OP_RETURN` included.
+const WITNESS_COMMITMENT_SIZE: usize = 38;
+
+#[derive(Default)]
+pub struct SignetParams {
+    /// Block challenge script, defaults to a 1-of-n multisig over `private_keys`.
+    pub challenge: Option<ScriptBuf>,
+    pub private_keys: Option<Vec<PrivateKey>>,
+    pub txs: Option<Vec<Transaction>>,
+    pub coinbase: Option<CoinbaseParams>,
+    /// Header fields to keep, bits default to the signet proof of work limit.
+    pub header: Option<HeaderParams>,
+}
+
+pub struct Signet {}
+
+impl Signet {
+    /// Build, sign and mine a signet block for the given challenge.
+    ///
+    /// Mining is done against the header bits, which is slow for the real signet target on
+    /// debug builds; pass easier bits through `header` for private test networks.
+    pub fn generate(params: SignetParams) -> Result<(Block, ScriptBuf), Box<dyn Error>> {
+        let private_keys = params
+            .private_keys
+            .unwrap_or_else(|| vec![PrivateKey::generate(bitcoin::NetworkKind::Test)]);
+        let challenge = match params.challenge {
+            Some(challenge) => challenge,
+            None => Self::multisig_challenge(&private_keys),
+        };
+
+        let mut coinbase_params = params.coinbase.unwrap_or_default();
+        coinbase_params.witness_commitment = Some(true);
+
+        let mut txdata = vec![GenerateTx::coinbase(coinbase_params)];
+        txdata.extend(params.txs.unwrap_or_default());
+
+        let mut header_params = params.header.unwrap_or_default();
+        header_params.merkle_root.get_or_insert_with(TxMerkleNode::all_zeros);
+        header_params
+            .bits
+            .get_or_insert_with(|| Params::SIGNET.max_attainable_target.to_compact_lossy());
+
+        let mut block = Block {
+            header: Header::random(header_params),
+            txdata,
+        };
+        GenerateBlock::recommit(&mut block);
+
+        Self::sign(&mut block, &challenge, &private_keys)?;
+        block.header = GenerateBlock::mine(block.header)?;
+
+        Ok((block, challenge))
+    }
+
+    /// A bare `1 <pubkey>... n OP_CHECKMULTISIG` challenge, the shape used by the default signet.
+    pub fn multisig_challenge(private_keys: &[PrivateKey]) -> ScriptBuf {
+        let secp = Secp256k1::new();
+        let mut builder = Builder::new().push_int(1);
+        for key in private_keys {
+            builder = builder.push_key(&PublicKey::from_private_key(&secp, key));
+        }
+        builder
+            .push_int(private_keys.len() as i64)
+            .push_opcode(OP_CHECKMULTISIG)
+            .into_script()
+    }
+
+    /// Sign the block for `challenge` and store the solution in the coinbase commitment.
+    ///
+    /// The merkle root is refreshed but the header is not mined again.
+    pub fn sign(
+        block: &mut Block,
+        challenge: &ScriptBuf,
+        private_keys: &[PrivateKey],
+    ) -> Result<(), Box<dyn Error>> {
+        // The signed data commits to the coinbase with an empty solution section.
+        Self::set_solution(block, Some(vec![]))?;
+        let (to_spend, mut to_sign) = Self::signet_txs(block, challenge);
+
+        let (script_sig, witness) = Self::solve(&to_sign, &to_spend, challenge, private_keys)?;
+        to_sign.input[0].script_sig = script_sig;
+        to_sign.input[0].witness = witness;
+
+        let mut solution = encode::serialize(&to_sign.input[0].script_sig);
+        to_sign.input[0]
+            .witness
+            .consensus_encode(&mut solution)
+            .expect("in-memory writers don't error");
+
+        Self::set_solution(block, Some(solution))
+    }
+
+    /// The raw solution (serialized scriptSig and witness stack) carried by the block.
+    pub fn solution(block: &Block) -> Option<Vec<u8>> {
+        let coinbase = block.txdata.first()?;
+        let index = witness_commitment_index(coinbase)?;
+        let script = &coinbase.output[index].script_pubkey;
+
+        script_pushes(&script.as_bytes()[WITNESS_COMMITMENT_SIZE..])
+            .into_iter()
+            .find(|push| push.len() > SIGNET_HEADER.len() && push.starts_with(&SIGNET_HEADER))
+            .map(|push| push[SIGNET_HEADER.len()..].to_vec())
+    }
+
+    /// Decode a raw solution into its scriptSig and witness.
+    pub fn decode_solution(solution: &[u8]) -> Result<(ScriptBuf, Witness), Box<dyn Error>> {
+        let (script_sig, consumed): (ScriptBuf, usize) = deserialize_partial(solution)?;
+        let (witness, rest): (Witness, usize) = deserialize_partial(&solution[consumed..])?;
+        if consumed + rest != solution.len() {
+            return Err("Extraneous data after the signet solution".into());
+        }
+        Ok((script_sig, witness))
+    }
+
+    /// Replace the signet section of the commitment, `None` removes it entirely.
+    ///
+    /// The header merkle root is refreshed since the coinbase txid changes.
+    pub fn set_solution(block: &mut Block, solution: Option<Vec<u8>>) -> Result<(), Box<dyn Error>> {
+        let coinbase = block.txdata.first_mut().ok_or("Block has no coinbase")?;
+        let index = witness_commitment_index(coinbase).ok_or("Coinbase has no witness commitment")?;
+        let script = &mut coinbase.output[index].script_pubkey;
+
+        let mut bytes = script.as_bytes()[..WITNESS_COMMITMENT_SIZE].to_vec();
+        if let Some(solution) = solution {
+            let mut section = SIGNET_HEADER.to_vec();
+            section.extend(solution);
+            let push = Builder::new()
+                .push_slice(PushBytesBuf::try_from(section)?)
+                .into_script();
+            bytes.extend_from_slice(push.as_bytes());
+        }
+        *script = ScriptBuf::from_bytes(bytes);
+
+        if let Some(merkle_root) = block.compute_merkle_root() {
+            block.header.merkle_root = merkle_root;
+        }
+        Ok(())
+    }
+
+    /// The `to_spend` and unsigned `to_sign` transactions of BIP325 for this block.
+    pub fn signet_txs(block: &Block, challenge: &ScriptBuf) -> (Transaction, Transaction) {
+        let mut block_data = Vec::with_capacity(72);
+        block.header.version.consensus_encode(&mut block_data).unwrap();
+        block.header.prev_blockhash.consensus_encode(&mut block_data).unwrap();
+        Self::signet_merkle_root(block).consensus_encode(&mut block_data).unwrap();
+        block.header.time.consensus_encode(&mut block_data).unwrap();
+
+        let to_spend = Transaction {
+            version: Version::non_standard(0),
+            lock_time: LockTime::ZERO,
+            input: vec![TxIn {
+                previous_output: OutPoint::null(),
+                script_sig: Builder::new()
+                    .push_int(0)
+                    .push_slice(PushBytesBuf::try_from(block_data).unwrap())
+                    .into_script(),
+                sequence: Sequence::ZERO,
+                witness: Witness::default(),
+            }],
+            output: vec![TxOut {
+                value: Amount::ZERO,
+                script_pubkey: challenge.clone(),
+            }],
+        };
+
+        let to_sign = Transaction {
+            version: Version::non_standard(0),
+            lock_time: LockTime::ZERO,
+            input: vec![TxIn {
+                previous_output: OutPoint {
+                    txid: to_spend.compute_txid(),
+                    vout: 0,
+                },
+                script_sig: ScriptBuf::new(),
+                sequence: Sequence::ZERO,
+                witness: Witness::default(),
+            }],
+            output: vec![TxOut {
+                value: Amount::ZERO,
+                script_pubkey: Builder::new().push_opcode(OP_RETURN).into_script(),
+            }],
+        };
+
+        (to_spend, to_sign)
+    }
+
+    /// Merkle root computed with the signet solution stripped down to its header.
+    pub fn signet_merkle_root(block: &Block) -> TxMerkleNode {
+        let mut modified = block.clone();
+        if Self::solution(block).is_some() {
+            // Only fails without a commitment, in which case there is no solution either.
+            let _ = Self::set_solution(&mut modified, Some(vec![]));
+        }
+
+        let hashes = modified.txdata.iter().map(|tx| tx.compute_txid().to_raw_hash());
+        merkle_tree::calculate_root(hashes)
+            .map(TxMerkleNode::from)
+            .unwrap_or_else(TxMerkleNode::all_zeros)
+    }
+
+    /// Produce a scriptSig and witness satisfying `challenge` with the given keys.
+    fn solve(
+        to_sign: &Transaction,
+        to_spend: &Transaction,
+        challenge: &ScriptBuf,
+        private_keys: &[PrivateKey],
+    ) -> Result<(ScriptBuf, Witness), Box<dyn Error>> {
+        let secp = Secp256k1::new();
+        let cache = SighashCache::new(to_sign);
+
+        if challenge.is_p2wpkh() {
+            let key = private_keys
+                .iter()
+                .find(|key| {
+                    let pubkey = PublicKey::from_private_key(&secp, key);
+                    pubkey
+                        .wpubkey_hash()
+                        .map(|hash| ScriptBuf::new_p2wpkh(&hash) == *challenge)
+                        .unwrap_or(false)
+                })
+                .ok_or("No private key matches the P2WPKH challenge")?;
+
+            let sighash = SighashCache::new(to_sign).p2wpkh_signature_hash(
+                0,
+                challenge,
+                Amount::ZERO,
+                EcdsaSighashType::All,
+            )?;
+            let signature = ecdsa::Signature {
+                signature: secp.sign_ecdsa(&Message::from_digest(sighash.to_byte_array()), &key.inner),
+                sighash_type: EcdsaSighashType::All,
+            };
+            let pubkey = PublicKey::from_private_key(&secp, key);
+
+            return Ok((ScriptBuf::new(), Witness::p2wpkh(&signature, &pubkey.inner)));
+        }
+
+        if challenge.is_p2tr() {
+            let keypair = private_keys
+                .iter()
+                .map(|key| Keypair::from_secret_key(&secp, &key.inner))
+                .find(|keypair| {
+                    let (internal_key, _) = keypair.x_only_public_key();
+                    ScriptBuf::new_p2tr(&secp, internal_key, None) == *challenge
+                })
+                .ok_or("No private key matches the P2TR challenge")?;
+
+            let sighash = SighashCache::new(to_sign).taproot_key_spend_signature_hash(
+                0,
+                &Prevouts::All(&to_spend.output),
+                TapSighashType::Default,
+            )?;
+            let tweaked = keypair.tap_tweak(&secp, None).to_keypair();
+            let signature = taproot::Signature {
+                signature: secp.sign_schnorr(&Message::from_digest(sighash.to_byte_array()), &tweaked),
+                sighash_type: TapSighashType::Default,
+            };
+
+            return Ok((ScriptBuf::new(), Witness::p2tr_key_spend(&signature)));
+        }
+
+        // Bare scripts: sign for every known key in script order, up to the multisig threshold.
+        let pubkeys: Vec<Vec<u8>> = script_pushes(challenge.as_bytes())
+            .into_iter()
+            .filter(|push| push.len() == 33 || push.len() == 65)
+            .collect();
+        let required = match challenge.first_opcode() {
+            Some(op) if challenge.is_multisig() => (op.to_u8() - 0x50) as usize,
+            _ => pubkeys.len(),
+        };
+
+        let sighash = cache.legacy_signature_hash(0, challenge, EcdsaSighashType::All.to_u32())?;
+        let message = Message::from_digest(sighash.to_byte_array());
+
+        let mut builder = Builder::new();
+        if challenge.is_multisig() {
+            // CHECKMULTISIG pops one extra element which must be empty (NULLDUMMY).
+            builder = builder.push_int(0);
+        }
+
+        let mut signed = 0;
+        for pubkey in &pubkeys {
+            if signed == required {
+                break;
+            }
+            let Some(key) = private_keys
+                .iter()
+                .find(|key| PublicKey::from_private_key(&secp, key).to_bytes() == *pubkey)
+            else {
+                continue;
+            };
+
+            let signature = ecdsa::Signature {
+                signature: secp.sign_ecdsa(&message, &key.inner),
+                sighash_type: EcdsaSighashType::All,
+            };
+            builder = builder.push_slice(PushBytesBuf::try_from(signature.to_vec())?);
+            signed += 1;
+        }
+
+        if signed < required {
+            return Err(format!("Only {} of {} required signatures could be made", signed, required).into());
+        }
+
+        Ok((builder.into_script(), Witness::default()))
+    }
+}
+
+/// Data pushed by the script, stopping at the first undecodable instruction.
+fn script_pushes(bytes: &[u8]) -> Vec<Vec<u8>> {
+    Script::from_bytes(bytes)
+        .instructions()
+        .map_while(Result::ok)
+        .filter_map(|instruction| match instruction {
+            Instruction::PushBytes(push) => Some(push.as_bytes().to_vec()),
+            Instruction::Op(_) => None,
+        })
+        .collect()
+}
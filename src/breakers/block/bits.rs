@@ -1,4 +1,9 @@
-use bitcoin::pow::CompactTarget;
+use bitcoin::{
+    block::Header,
+    params::Params,
+    pow::{CompactTarget, Target, Work},
+    Network,
+};
 
 /// Processor for difficulty bits field modifications
 pub struct BitsProcessor;
@@ -37,71 +42,99 @@ impl BitsProcessor {
         
         // Generate within reasonable difficulty range
         let exponent = rng.random_range(0x1d..=0x20); // Reasonable exponent range
-        let mantissa = rng.random_range(0x008000..=0xffffff); // Valid mantissa range
+        let mantissa = rng.random_range(0x008000..=0x7fffff); // Normalized, sign bit clear
         
         (exponent << 24) | mantissa
     }
 
-    /// Increment difficulty (make mining harder)
+    /// Make mining `factor` times harder, dividing the target
     pub fn increase_difficulty(bits: u32, factor: f64) -> u32 {
-        let target = Self::bits_to_target(bits);
-        let new_target = (target as f64 / factor) as u32;
-        Self::target_to_bits(new_target.max(1)) // Ensure non-zero
+        let Some((numerator, denominator)) = Self::factor_to_ratio(factor) else {
+            return bits;
+        };
+        let target = Self::mul_div(Self::bits_to_target(bits), denominator, numerator);
+        Self::target_to_bits(target.max(Target::from_le_bytes(Self::ONE))) // Ensure non-zero
     }
 
-    /// Decrease difficulty (make mining easier)
+    /// Make mining `factor` times easier, multiplying the target
     pub fn decrease_difficulty(bits: u32, factor: f64) -> u32 {
-        let target = Self::bits_to_target(bits);
-        let new_target = (target as f64 * factor) as u32;
-        Self::target_to_bits(new_target)
+        let Some((numerator, denominator)) = Self::factor_to_ratio(factor) else {
+            return bits;
+        };
+        Self::target_to_bits(Self::mul_div(Self::bits_to_target(bits), numerator, denominator))
     }
 
-    /// Convert compact bits representation to target value (simplified)
-    pub fn bits_to_target(bits: u32) -> u32 {
-        let exponent = (bits >> 24) & 0xff;
-        let mantissa = bits & 0xffffff;
-        
-        if exponent <= 3 {
-            mantissa >> (8 * (3 - exponent))
+    /// Convert compact bits to the target they encode, ignoring the sign bit
+    pub fn bits_to_target(bits: u32) -> Target {
+        Self::decode_compact(bits).target
+    }
+
+    /// Decode compact bits the way Bitcoin Core's `SetCompact` does, reporting the sign bit
+    /// and encodings whose value does not fit in 256 bits
+    pub fn decode_compact(bits: u32) -> DecodedBits {
+        let size = (bits >> 24) as usize;
+        let mut word = bits & 0x007fffff;
+
+        let mut bytes = [0u8; 32];
+        if size <= 3 {
+            word >>= 8 * (3 - size);
+            bytes[..4].copy_from_slice(&word.to_le_bytes());
         } else {
-            mantissa << (8 * (exponent - 3))
+            // Bytes shifted past the 256th bit are lost, as in Core.
+            for (i, byte) in word.to_le_bytes().iter().take(3).enumerate() {
+                if let Some(slot) = bytes.get_mut(size - 3 + i) {
+                    *slot = *byte;
+                }
+            }
         }
-    }
 
-    /// Convert target value to compact bits representation (simplified)
-    pub fn target_to_bits(target: u32) -> u32 {
-        if target == 0 {
-            return 0;
+        DecodedBits {
+            target: Target::from_le_bytes(bytes),
+            negative: word != 0 && (bits & 0x00800000) != 0,
+            overflow: word != 0
+                && (size > 34 || (word > 0xff && size > 33) || (word > 0xffff && size > 32)),
         }
+    }
 
-        let mut size = (32 - target.leading_zeros()) as u8;
-        let mut compact = if size <= 3 {
-            target << (8 * (3 - size as u32))
-        } else {
-            target >> (8 * (size as u32 - 3))
-        };
+    /// Convert a target to its compact bits representation, rounding down
+    pub fn target_to_bits(target: Target) -> u32 {
+        target.to_compact_lossy().to_consensus()
+    }
 
-        // If the sign bit is set, we need to adjust
-        if (compact & 0x00800000) != 0 {
-            compact >>= 8;
-            size += 1;
+    /// Expected number of hashes needed to meet the target encoded in `bits`, zero for invalid
+    /// bits as in Core's `GetBlockProof`
+    pub fn work(bits: u32) -> Work {
+        if !Self::is_valid_bits(bits) {
+            return Work::from_le_bytes([0u8; 32]);
         }
+        Self::bits_to_target(bits).to_work()
+    }
 
-        ((size as u32) << 24) | compact
+    /// Total work of a chain of headers, as compared when choosing the best chain, saturating
+    /// at the largest 256 bits value
+    pub fn chainwork(headers: &[Header]) -> Work {
+        headers
+            .iter()
+            .map(|header| Self::work(header.bits.to_consensus()))
+            .fold(Work::from_le_bytes([0u8; 32]), Self::saturating_add)
     }
 
     /// Validate bits format
     pub fn is_valid_bits(bits: u32) -> bool {
-        let exponent = (bits >> 24) & 0xff;
-        let mantissa = bits & 0xffffff;
-        
-        // Basic validation: exponent should be reasonable, mantissa non-zero for valid target
-        exponent >= 0x03 && exponent <= 0x20 && mantissa != 0
+        let decoded = Self::decode_compact(bits);
+        !decoded.negative && !decoded.overflow && decoded.target != Target::ZERO
+    }
+
+    /// Check whether `bits` is valid and within the proof of work limit of `network`
+    pub fn is_within_pow_limit(bits: u32, network: Network) -> bool {
+        Self::is_valid_bits(bits)
+            && Self::bits_to_target(bits) <= Params::new(network).max_attainable_target
     }
 
     /// Check if bits represent minimum difficulty
     pub fn is_min_difficulty(bits: u32) -> bool {
-        bits >= 0x207fffff
+        Self::is_valid_bits(bits)
+            && Self::bits_to_target(bits) >= Params::REGTEST.max_attainable_target
     }
 
     /// Flip specific bits in the difficulty target
@@ -119,6 +152,78 @@ impl BitsProcessor {
             DifficultyLevel::VeryHard => 0x1c0fffff,
         }
     }
+
+    const ONE: [u8; 32] = {
+        let mut one = [0u8; 32];
+        one[0] = 1;
+        one
+    };
+
+    /// Express a positive factor as a ratio over 2^32
+    fn factor_to_ratio(factor: f64) -> Option<(u64, u64)> {
+        let denominator = 1u64 << 32;
+        let numerator = (factor * denominator as f64).round();
+        (factor.is_finite() && numerator >= 1.0 && numerator < u64::MAX as f64)
+            .then_some((numerator as u64, denominator))
+    }
+
+    /// Add two amounts of work, saturating at the largest 256 bits value
+    fn saturating_add(total: Work, work: Work) -> Work {
+        let (total, work) = (total.to_le_bytes(), work.to_le_bytes());
+        let mut sum = [0u8; 32];
+        let mut carry = 0u16;
+        for (i, byte) in sum.iter_mut().enumerate() {
+            let value = total[i] as u16 + work[i] as u16 + carry;
+            *byte = value as u8;
+            carry = value >> 8;
+        }
+
+        if carry != 0 {
+            return Work::from_le_bytes([0xff; 32]);
+        }
+        Work::from_le_bytes(sum)
+    }
+
+    /// Compute `target * numerator / denominator`, saturating at the largest 256 bits value
+    fn mul_div(target: Target, numerator: u64, denominator: u64) -> Target {
+        let bytes = target.to_le_bytes();
+        let mut limbs = [0u64; 5];
+        for (i, limb) in limbs.iter_mut().take(4).enumerate() {
+            *limb = u64::from_le_bytes(bytes[i * 8..i * 8 + 8].try_into().expect("8 bytes"));
+        }
+
+        let mut carry = 0u128;
+        for limb in limbs.iter_mut() {
+            let product = *limb as u128 * numerator as u128 + carry;
+            *limb = product as u64;
+            carry = product >> 64;
+        }
+
+        let mut remainder = 0u128;
+        for limb in limbs.iter_mut().rev() {
+            let dividend = (remainder << 64) | *limb as u128;
+            *limb = (dividend / denominator as u128) as u64;
+            remainder = dividend % denominator as u128;
+        }
+
+        // `Target::MAX` is the mainnet limit, saturate at 2^256 - 1 instead.
+        if limbs[4] != 0 {
+            return Target::from_le_bytes([0xff; 32]);
+        }
+        let mut result = [0u8; 32];
+        for (i, limb) in limbs.iter().take(4).enumerate() {
+            result[i * 8..i * 8 + 8].copy_from_slice(&limb.to_le_bytes());
+        }
+        Target::from_le_bytes(result)
+    }
+}
+
+/// Compact bits decoded with Bitcoin Core's flags
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DecodedBits {
+    pub target: Target,
+    pub negative: bool,
+    pub overflow: bool,
 }
 
 /// Difficulty levels for testing
@@ -1,6 +1,19 @@
-use bitcoin::blockdata::block::{Block, Header};
+use std::collections::HashSet;
+use std::error::Error;
+
+use bitcoin::blockdata::block::{Block, Header, Version};
+use bitcoin::script::ScriptBuf;
+use bitcoin::{Amount, BlockHash, Network, OutPoint, TxMerkleNode};
+use super::coinbase::CoinbaseProcessor;
 use super::header::HeaderProcessor;
+use super::merkle_root::MerkleRootProcessor;
+use super::pow::PowProcessor;
+use super::version::VersionProcessor;
+use super::timestamp::{TimestampBreak, TimestampProcessor};
+use super::witness_commitment::WitnessCommitmentProcessor;
 use super::decoder_tools::BlockUtils;
+use crate::block::generator::GenerateBlock;
+use crate::breakers::transaction::{InvalidationFlag, TransactionInvalidator};
 
 // Re-export the enum and config from the original design
 #[derive(Debug, Clone, PartialEq)]
@@ -11,7 +24,68 @@ pub enum BlockField {
     Timestamp,
     Bits,
     Nonce,
+    /// Header fields only, transaction level corruptions have to be requested explicitly.
     All,
+    /// Drop the coinbase, leaving the first transaction as a regular one.
+    RemoveCoinbase,
+    /// Insert a second coinbase right after the first one.
+    DuplicateCoinbase,
+    /// Reverse the transactions after the coinbase, so spends come before their parents.
+    ReorderTransactions,
+    /// Corrupt the transaction at `corrupt_tx_index` with `TransactionInvalidator`.
+    CorruptTransaction,
+    /// Duplicate the trailing transactions while keeping the merkle root (CVE-2012-2459).
+    DuplicateTransactions,
+    /// Append a transaction of 64 bytes without witness, the size of an inner merkle node.
+    SixtyFourByteTransaction,
+    /// Remove the witness commitment while witness data is present.
+    MissingWitnessCommitment,
+    /// Commit to the wrong witness merkle root.
+    WrongWitnessCommitment,
+    /// Move the witness commitment out of the coinbase.
+    MisplacedWitnessCommitment,
+    /// Add a second, wrong, commitment after the real one.
+    MultipleWitnessCommitments,
+    /// Give the coinbase a witness reserved value that is not 32 bytes.
+    WrongWitnessReservedValueSize,
+    /// Keep witness data in a block without any commitment.
+    UnexpectedWitness,
+    /// Encode the BIP34 height with a superfluous trailing zero byte.
+    NonMinimalHeight,
+    /// Encode a BIP34 height off by one.
+    WrongHeight,
+    /// Remove the BIP34 height from the coinbase scriptSig.
+    MissingHeight,
+    /// Shrink the coinbase scriptSig below 2 bytes.
+    ShortCoinbaseScriptSig,
+    /// Grow the coinbase scriptSig above 100 bytes.
+    LongCoinbaseScriptSig,
+    /// Pay out one satoshi more than the subsidy plus `coinbase_fees`.
+    ExcessiveCoinbaseValue,
+    /// Give the coinbase input a non-null prevout.
+    NonNullCoinbasePrevout,
+    /// Spend a coinbase before maturity, the block's own unless `immature_coinbase` is set.
+    ImmatureCoinbaseSpend,
+    /// Grind a nonce whose hash lands just above the target.
+    HighHash,
+    /// Set bits to the smallest target above the network's proof of work limit.
+    BitsAbovePowLimit,
+    /// Set the sign bit of the compact target.
+    NegativeBits,
+    /// Set bits to a target that overflows 256 bits.
+    OverflowBits,
+    /// Set bits to a target of zero.
+    ZeroBits,
+    /// Set the time to the median time past of `prev_times`.
+    TimeAtMedianTimePast,
+    /// Set the time one second below the median time past of `prev_times`.
+    TimeBelowMedianTimePast,
+    /// Set the time one second past the future limit of `adjusted_time`.
+    TimeTooNew,
+    /// Set the time one second beyond the BIP94 limit before the last of `prev_times`.
+    Timewarp,
+    /// Signal a version bit no deployment has used.
+    UnknownDeploymentVersion,
 }
 
 // Configuration for block processing
@@ -21,6 +95,42 @@ pub struct ProcessingConfig {
     pub version_override: Option<i32>,
     pub timestamp_offset: Option<i64>, // seconds to add/subtract
     pub randomize_hashes: bool,
+    /// Transaction corrupted by `CorruptTransaction`, the last one when not set.
+    pub corrupt_tx_index: Option<usize>,
+    /// Flags used by `CorruptTransaction`, every field when empty.
+    pub tx_flags: HashSet<InvalidationFlag>,
+    /// Refresh the merkle root and witness commitment after transaction level changes,
+    /// so only the targeted rule is violated.
+    pub recompute_merkle_root: bool,
+    /// Height used by the coinbase breakers, read from the coinbase scriptSig when not set.
+    /// `Version` breaks against it as well, assuming every soft fork is active when neither
+    /// is available.
+    pub coinbase_height: Option<u32>,
+    /// Fees the coinbase may claim on top of the subsidy, in satoshis.
+    pub coinbase_fees: Option<u64>,
+    /// Coinbase output spent by `ImmatureCoinbaseSpend`.
+    pub immature_coinbase: Option<OutPoint>,
+    /// Network whose proof of work limit `BitsAbovePowLimit` exceeds and whose activation
+    /// heights `Version` breaks against, regtest when not set.
+    pub network: Option<Network>,
+    /// Value written by `PrevBlockHash` instead of a random or zero hash.
+    pub prev_blockhash_override: Option<BlockHash>,
+    /// Value written by `MerkleRoot` instead of a random or zero root.
+    pub merkle_root_override: Option<TxMerkleNode>,
+    /// Absolute time written by `Timestamp`, shifted by `timestamp_offset` when both are set.
+    pub time_override: Option<u32>,
+    /// Compact target written by `Bits` instead of the XOR corruption.
+    pub bits_override: Option<u32>,
+    /// Nonce written by `Nonce` instead of its bitwise inverse.
+    pub nonce_override: Option<u32>,
+    /// Bit positions of the previous block hash flipped by `PrevBlockHash`.
+    pub prev_blockhash_flips: Vec<usize>,
+    /// Bit positions of the merkle root flipped by `MerkleRoot`.
+    pub merkle_root_flips: Vec<usize>,
+    /// Timestamps of the ancestors of the block, oldest first, used by the time rule breakers.
+    pub prev_times: Vec<u32>,
+    /// Network-adjusted time used by `TimeTooNew`, the system time when not set.
+    pub adjusted_time: Option<u32>,
 }
 
 
@@ -48,24 +158,257 @@ impl BlockProcessor {
     }
 
     // Process the entire block header based on configuration
-    pub fn process_block_header(&self, header: &Header) -> Header {
-        HeaderProcessor::process_header(
-            header,
-            self.config.version_override,
-            self.config.timestamp_offset,
-            self.config.randomize_hashes,
-            &self.config.fields_to_modify,
-        )
+    pub fn process_block_header(&self, header: &Header) -> Result<Header, Box<dyn Error>> {
+        self.process_header(header, self.config.coinbase_height)
+    }
+
+    // Process the header of a block at `coinbase_height`
+    fn process_header(
+        &self,
+        header: &Header,
+        coinbase_height: Option<u32>,
+    ) -> Result<Header, Box<dyn Error>> {
+        let config = ProcessingConfig {
+            coinbase_height,
+            ..self.config.clone()
+        };
+        let mut header = HeaderProcessor::process_header_with_config(header, &config)?;
+
+        if self.config.fields_to_modify.contains(&BlockField::UnknownDeploymentVersion) {
+            header.version = Version::from_consensus(VersionProcessor::unknown_deployment());
+        }
+        Ok(header)
+    }
+
+    // Process an entire block, mining it again when it had a valid proof of work
+    pub fn process_block(&self, block: &Block) -> Result<Block, Box<dyn Error>> {
+        // Read before the coinbase breakers get a chance to corrupt it.
+        let height = self.config.coinbase_height.or_else(|| CoinbaseProcessor::height(block));
+        let mut modified_block = block.clone();
+        self.process_transactions(&mut modified_block)?;
+
+        if self.config.recompute_merkle_root {
+            GenerateBlock::recommit(&mut modified_block);
+        }
+
+        // Commitment breaks come after the recommit, which would otherwise undo them.
+        let commitment_broken = self.process_witness_commitment(&mut modified_block);
+        if commitment_broken && self.config.recompute_merkle_root {
+            if let Some(merkle_root) = modified_block.compute_merkle_root() {
+                modified_block.header.merkle_root = merkle_root;
+            }
+        }
+
+        modified_block.header = self.process_header(&modified_block.header, height)?;
+        modified_block.header = self.process_time_rules(&modified_block.header)?;
+        modified_block.header = self.process_pow(&modified_block.header)?;
+
+        // Mine the final header so the block is not rejected as `high-hash` before the targeted
+        // rule, unless its proof of work is broken on purpose or was not valid to begin with.
+        if !self.breaks_pow() && block.header.target().is_met_by(block.block_hash()) {
+            modified_block.header = GenerateBlock::mine(modified_block.header)?;
+        }
+        Ok(modified_block)
+    }
+
+    // Whether any requested field changes the bits or the nonce
+    fn breaks_pow(&self) -> bool {
+        self.config.fields_to_modify.iter().any(|field| {
+            matches!(
+                field,
+                BlockField::All
+                    | BlockField::Bits
+                    | BlockField::Nonce
+                    | BlockField::HighHash
+                    | BlockField::BitsAbovePowLimit
+                    | BlockField::NegativeBits
+                    | BlockField::OverflowBits
+                    | BlockField::ZeroBits
+            )
+        })
     }
 
-    // Process an entire block
-    pub fn process_block(&self, block: &Block) -> Block {
-        let modified_header = self.process_block_header(&block.header);
-        
-        Block {
-            header: modified_header,
-            txdata: block.txdata.clone(),
+    // Break the timestamp against the ancestors, overriding the plain timestamp corruption
+    fn process_time_rules(&self, header: &Header) -> Result<Header, Box<dyn Error>> {
+        let mut header = *header;
+
+        for field in &self.config.fields_to_modify {
+            let mode = match field {
+                BlockField::TimeAtMedianTimePast => TimestampBreak::MedianTimePast,
+                BlockField::TimeBelowMedianTimePast => TimestampBreak::BelowMedianTimePast,
+                BlockField::TimeTooNew => TimestampBreak::TooNew,
+                BlockField::Timewarp => TimestampBreak::Timewarp,
+                _ => continue,
+            };
+            let prev_times = &self.config.prev_times;
+            let adjusted_time = self.config.adjusted_time;
+            header.time = TimestampProcessor::broken_time(prev_times, mode, adjusted_time)
+                .ok_or_else(|| format!("{:?} needs the timestamps of the previous blocks", field))?;
         }
+
+        Ok(header)
+    }
+
+    // Break the proof of work, once every other header field is final
+    fn process_pow(&self, header: &Header) -> Result<Header, Box<dyn Error>> {
+        let fields = &self.config.fields_to_modify;
+        let mut header = *header;
+
+        if fields.contains(&BlockField::BitsAbovePowLimit) {
+            let network = self.config.network.unwrap_or(Network::Regtest);
+            header.bits = PowProcessor::above_pow_limit(network);
+        }
+        if fields.contains(&BlockField::NegativeBits) {
+            header.bits = PowProcessor::negative(header.bits);
+        }
+        if fields.contains(&BlockField::OverflowBits) {
+            header.bits = PowProcessor::overflow();
+        }
+        if fields.contains(&BlockField::ZeroBits) {
+            header.bits = PowProcessor::zero();
+        }
+        if fields.contains(&BlockField::HighHash) {
+            header = PowProcessor::high_hash(&header)?;
+        }
+
+        Ok(header)
+    }
+
+    // Apply the witness commitment corruptions, returning whether any was requested
+    fn process_witness_commitment(&self, block: &mut Block) -> bool {
+        let mut processed = false;
+
+        for field in &self.config.fields_to_modify {
+            match field {
+                BlockField::MissingWitnessCommitment => {
+                    WitnessCommitmentProcessor::remove_commitment(block)
+                }
+                BlockField::WrongWitnessCommitment => {
+                    WitnessCommitmentProcessor::corrupt_commitment(block)
+                }
+                BlockField::MisplacedWitnessCommitment => {
+                    WitnessCommitmentProcessor::misplace_commitment(block)
+                }
+                BlockField::MultipleWitnessCommitments => {
+                    WitnessCommitmentProcessor::add_second_commitment(block)
+                }
+                BlockField::WrongWitnessReservedValueSize => {
+                    WitnessCommitmentProcessor::resize_reserved_value(block)
+                }
+                BlockField::UnexpectedWitness => {
+                    WitnessCommitmentProcessor::unexpected_witness(block)
+                }
+                _ => continue,
+            }
+            processed = true;
+        }
+
+        processed
+    }
+
+    // Apply the coinbase corruptions
+    fn process_coinbase(&self, block: &mut Block) -> Result<(), Box<dyn Error>> {
+        let height = self.config.coinbase_height.or_else(|| CoinbaseProcessor::height(block));
+        let require_height = || {
+            height.ok_or("The coinbase carries no height, set it with --coinbase-height")
+        };
+
+        for field in &self.config.fields_to_modify {
+            match field {
+                BlockField::NonMinimalHeight => {
+                    CoinbaseProcessor::non_minimal_height(block, require_height()?)
+                }
+                BlockField::WrongHeight => {
+                    CoinbaseProcessor::wrong_height(block, require_height()?)
+                }
+                BlockField::MissingHeight => CoinbaseProcessor::missing_height(block),
+                BlockField::ShortCoinbaseScriptSig => CoinbaseProcessor::short_script_sig(block),
+                BlockField::LongCoinbaseScriptSig => CoinbaseProcessor::long_script_sig(block),
+                BlockField::ExcessiveCoinbaseValue => {
+                    let fees = Amount::from_sat(self.config.coinbase_fees.unwrap_or_default());
+                    CoinbaseProcessor::excessive_value(block, require_height()?, fees)?
+                }
+                BlockField::ImmatureCoinbaseSpend => {
+                    CoinbaseProcessor::spend_immature(block, self.config.immature_coinbase)
+                }
+                _ => {}
+            }
+        }
+
+        // Last, as the null prevout is what makes the first transaction a coinbase.
+        if self.config.fields_to_modify.contains(&BlockField::NonNullCoinbasePrevout) {
+            CoinbaseProcessor::non_null_prevout(block);
+        }
+
+        Ok(())
+    }
+
+    // Apply the transaction level corruptions, in a fixed order
+    fn process_transactions(&self, block: &mut Block) -> Result<(), Box<dyn Error>> {
+        self.process_coinbase(block)?;
+        let fields = &self.config.fields_to_modify;
+
+        if fields.contains(&BlockField::CorruptTransaction) && !block.txdata.is_empty() {
+            let index = self.config.corrupt_tx_index.unwrap_or(block.txdata.len() - 1);
+            if index >= block.txdata.len() {
+                return Err(format!(
+                    "No transaction {} to corrupt, the block has {}",
+                    index,
+                    block.txdata.len()
+                )
+                .into());
+            }
+            let flags = if self.config.tx_flags.is_empty() {
+                HashSet::from([InvalidationFlag::All])
+            } else {
+                self.config.tx_flags.clone()
+            };
+            block.txdata[index] =
+                TransactionInvalidator::invalidate(block.txdata[index].clone(), &flags)?;
+        }
+
+        if fields.contains(&BlockField::ReorderTransactions) {
+            if block.txdata.len() > 2 {
+                block.txdata[1..].reverse();
+            } else if block.txdata.len() == 2 {
+                // Nothing to reorder behind the coinbase, so move the coinbase itself.
+                block.txdata.swap(0, 1);
+            }
+        }
+
+        if fields.contains(&BlockField::DuplicateCoinbase) {
+            if let Some(coinbase) = block.txdata.first() {
+                let mut duplicate = coinbase.clone();
+                // Tweak the scriptSig so both coinbases keep distinct txids.
+                if let Some(input) = duplicate.input.first_mut() {
+                    let mut script_sig = input.script_sig.to_bytes();
+                    // Coinbase scriptSigs are capped at 100 bytes.
+                    if script_sig.len() >= 100 {
+                        script_sig[99] ^= 0x01;
+                    } else {
+                        script_sig.push(0x51);
+                    }
+                    input.script_sig = ScriptBuf::from_bytes(script_sig);
+                }
+                block.txdata.insert(1, duplicate);
+            }
+        }
+
+        if fields.contains(&BlockField::RemoveCoinbase) && !block.txdata.is_empty() {
+            block.txdata.remove(0);
+        }
+
+        if fields.contains(&BlockField::SixtyFourByteTransaction) {
+            block.txdata.push(MerkleRootProcessor::sixty_four_byte_transaction());
+        }
+
+        // Last, so the duplicated transactions are the final ones. Blocks whose transaction
+        // count is a power of two cannot be mutated this way.
+        if fields.contains(&BlockField::DuplicateTransactions) {
+            *block = MerkleRootProcessor::duplicate_transactions(block)?;
+        }
+
+        Ok(())
     }
 
     // Update configuration
@@ -109,12 +452,12 @@ impl BlockProcessor {
 pub struct BlockBreaker;
 
 impl BlockBreaker {
-    pub fn break_all_fields(block: &Block) -> Block {
+    pub fn break_all_fields(block: &Block) -> Result<Block, Box<dyn Error>> {
         let processor = BlockProcessor::with_default_config();
         processor.process_block(block)
     }
 
-    pub fn break_specific_fields(block: &Block, fields: Vec<BlockField>) -> Block {
+    pub fn break_specific_fields(block: &Block, fields: Vec<BlockField>) -> Result<Block, Box<dyn Error>> {
         let config = ProcessingConfig {
             fields_to_modify: fields,
             ..Default::default()
@@ -123,23 +466,26 @@ impl BlockBreaker {
         processor.process_block(block)
     }
 
-    pub fn break_with_config(block: &Block, config: ProcessingConfig) -> Block {
+    pub fn break_with_config(block: &Block, config: ProcessingConfig) -> Result<Block, Box<dyn Error>> {
         let processor = BlockProcessor::new(config);
         processor.process_block(block)
     }
 
-    pub fn break_header_fields(header: &Header, fields: Vec<BlockField>) -> Block {
+    pub fn break_header_fields(
+        header: &Header,
+        fields: Vec<BlockField>,
+    ) -> Result<Block, Box<dyn Error>> {
         let config = ProcessingConfig {
             fields_to_modify: fields,
             ..Default::default()
         };
         let processor = BlockProcessor::new(config);
-        let modified_header = processor.process_block_header(header);
-        BlockUtils::create_minimal_block_from_header(modified_header)
+        let modified_header = processor.process_block_header(header)?;
+        Ok(BlockUtils::create_minimal_block_from_header(modified_header))
     }
 
     // Break single field with default settings
-    pub fn break_single_field(block: &Block, field: BlockField) -> Block {
+    pub fn break_single_field(block: &Block, field: BlockField) -> Result<Block, Box<dyn Error>> {
         Self::break_specific_fields(block, vec![field])
     }
 
@@ -150,12 +496,13 @@ impl BlockBreaker {
         version_override: Option<i32>,
         timestamp_offset: Option<i64>,
         randomize_hashes: bool,
-    ) -> Block {
+    ) -> Result<Block, Box<dyn Error>> {
         let config = ProcessingConfig {
             fields_to_modify: fields,
             version_override,
             timestamp_offset,
             randomize_hashes,
+            ..Default::default()
         };
         Self::break_with_config(block, config)
     }
@@ -0,0 +1,168 @@
+use std::error::Error;
+use std::str::FromStr;
+
+use bitcoin::{block::Header, hashes::Hash, BlockHash};
+use rand::Rng;
+
+use crate::block::generator::GenerateBlock;
+use crate::block::random::chain::{ChainParams, HeaderChain};
+
+/// Ways of breaking a header chain as a whole, for headers-first sync tests.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChainBreak {
+    /// Rebuild the chain on top of a block nobody knows.
+    Orphan,
+    /// Branch off below the checkpoint at the given index, with more work than the chain.
+    ForkBelowCheckpoint,
+    /// A competing chain from the same parent with one header less.
+    LowerWork,
+    /// Repeat the header at the given index right after itself.
+    DuplicateHeader,
+    /// Drop the header at the given index, leaving a gap in the batch.
+    NonContinuous,
+}
+
+impl FromStr for ChainBreak {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "orphan" | "disconnected" => Ok(Self::Orphan),
+            "checkpoint-fork" | "fork-below-checkpoint" => Ok(Self::ForkBelowCheckpoint),
+            "lower-work" => Ok(Self::LowerWork),
+            "duplicate" | "duplicate-header" => Ok(Self::DuplicateHeader),
+            "non-continuous" | "gap" => Ok(Self::NonContinuous),
+            _ => Err(format!("Unknown chain break mode '{}'", s)),
+        }
+    }
+}
+
+/// Processor for header chain modifications
+pub struct ChainProcessor;
+
+impl ChainProcessor {
+    /// Break `chain` as a whole, returning the headers to announce in order.
+    ///
+    /// `index` picks the checkpoint, duplicated or dropped header, the middle one when not given.
+    pub fn process_chain(
+        chain: &HeaderChain,
+        mode: ChainBreak,
+        index: Option<usize>,
+    ) -> Result<Vec<Header>, Box<dyn Error>> {
+        let index = index.unwrap_or(chain.headers.len() / 2);
+
+        match mode {
+            ChainBreak::Orphan => Self::orphan(chain),
+            ChainBreak::ForkBelowCheckpoint => Self::fork_below_checkpoint(chain, index),
+            ChainBreak::LowerWork => Self::lower_work(chain),
+            ChainBreak::DuplicateHeader => Self::duplicate_header(chain, index),
+            ChainBreak::NonContinuous => Self::non_continuous(chain, index),
+        }
+    }
+
+    /// The same chain mined again on top of a random, unknown, previous block hash.
+    pub fn orphan(chain: &HeaderChain) -> Result<Vec<Header>, Box<dyn Error>> {
+        let first = chain.headers.first().ok_or("Header chain is empty")?;
+
+        let orphan = GenerateBlock::header_chain(ChainParams {
+            length: Some(chain.headers.len()),
+            prev_blockhash: Some(BlockHash::from_byte_array(rand::rng().random())),
+            prev_times: Some(chain.prev_times.clone()),
+            start_time: Some(first.time),
+            version: Some(first.version),
+            bits: Some(first.bits),
+            ..Default::default()
+        })?;
+        Ok(orphan.headers)
+    }
+
+    /// A branch whose first header sits right below the checkpoint at `checkpoint`, replacing
+    /// the header before it, and which reaches one header past the tip.
+    ///
+    /// The branch has more work than the chain, so only the checkpoint rejects it
+    /// (`bad-fork-prior-to-checkpoint`).
+    pub fn fork_below_checkpoint(
+        chain: &HeaderChain,
+        checkpoint: usize,
+    ) -> Result<Vec<Header>, Box<dyn Error>> {
+        if checkpoint == 0 || checkpoint >= chain.headers.len() {
+            return Err(format!(
+                "Checkpoint index must be between 1 and {}",
+                chain.headers.len().saturating_sub(1)
+            )
+            .into());
+        }
+
+        let fork_index = checkpoint - 1;
+        let length = chain.headers.len() - fork_index + 1;
+        Ok(Self::branch(chain, fork_index, length)?.headers)
+    }
+
+    /// A branch from the parent of the chain with one header less, so less cumulative work.
+    pub fn lower_work(chain: &HeaderChain) -> Result<Vec<Header>, Box<dyn Error>> {
+        if chain.headers.len() < 2 {
+            return Err("Header chain needs at least 2 headers to be outworked".into());
+        }
+        Ok(Self::branch(chain, 0, chain.headers.len() - 1)?.headers)
+    }
+
+    /// The chain with the header at `index` sent twice in a row.
+    pub fn duplicate_header(
+        chain: &HeaderChain,
+        index: usize,
+    ) -> Result<Vec<Header>, Box<dyn Error>> {
+        let header = *chain.headers.get(index).ok_or("Header index out of range")?;
+
+        let mut headers = chain.headers.clone();
+        headers.insert(index + 1, header);
+        Ok(headers)
+    }
+
+    /// The chain without the header at `index`, so the next one does not connect to the
+    /// header sent before it.
+    pub fn non_continuous(
+        chain: &HeaderChain,
+        index: usize,
+    ) -> Result<Vec<Header>, Box<dyn Error>> {
+        // Dropping either end keeps the batch continuous.
+        if index == 0 || index + 1 >= chain.headers.len() {
+            return Err(format!(
+                "Header index must be between 1 and {}",
+                chain.headers.len().saturating_sub(2)
+            )
+            .into());
+        }
+
+        let mut headers = chain.headers.clone();
+        headers.remove(index);
+        Ok(headers)
+    }
+
+    /// Whether every header of `headers` commits to the one sent before it.
+    pub fn is_continuous(headers: &[Header]) -> bool {
+        headers
+            .windows(2)
+            .all(|pair| pair[1].prev_blockhash == pair[0].block_hash())
+    }
+
+    /// A new chain of `length` headers sharing the first `fork_index` headers of `chain`.
+    fn branch(
+        chain: &HeaderChain,
+        fork_index: usize,
+        length: usize,
+    ) -> Result<HeaderChain, Box<dyn Error>> {
+        let replaced = chain.headers[fork_index];
+        let times = chain.times();
+
+        GenerateBlock::header_chain(ChainParams {
+            length: Some(length),
+            prev_blockhash: Some(replaced.prev_blockhash),
+            prev_times: Some(times[..chain.prev_times.len() + fork_index].to_vec()),
+            // A later time keeps the first header apart from the one it replaces.
+            start_time: Some(replaced.time.saturating_add(1)),
+            version: Some(replaced.version),
+            bits: Some(replaced.bits),
+            ..Default::default()
+        })
+    }
+}
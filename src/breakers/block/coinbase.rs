@@ -0,0 +1,206 @@
+use std::error::Error;
+
+use bitcoin::{
+    absolute::LockTime,
+    hashes::Hash,
+    opcodes::{all::OP_NOP, Class, ClassifyContext},
+    script::{self, Builder, Instruction, PushBytesBuf},
+    transaction::Version,
+    Amount, Block, OutPoint, ScriptBuf, Sequence, Transaction, TxIn, TxOut, Txid, Witness,
+};
+use rand::Rng;
+
+use super::witness_commitment::WitnessCommitmentProcessor;
+use crate::transaction::random::coinbase::{block_subsidy, witness_commitment_index};
+
+/// Coinbase scriptSigs must be between 2 and 100 bytes long.
+pub const MIN_COINBASE_SCRIPT_SIG: usize = 2;
+pub const MAX_COINBASE_SCRIPT_SIG: usize = 100;
+
+/// Processor for coinbase transaction modifications
+pub struct CoinbaseProcessor;
+
+impl CoinbaseProcessor {
+    /// Height pushed first in the coinbase scriptSig, minimal or not (BIP34).
+    pub fn height(block: &Block) -> Option<u32> {
+        let script_sig = &block.txdata.first()?.input.first()?.script_sig;
+
+        let height = match script_sig.instructions().next()?.ok()? {
+            Instruction::PushBytes(push) => {
+                script::read_scriptint_non_minimal(push.as_bytes()).ok()?
+            }
+            Instruction::Op(op) => match op.classify(ClassifyContext::Legacy) {
+                Class::PushNum(n) => n as i64,
+                _ => return None,
+            },
+        };
+        u32::try_from(height).ok()
+    }
+
+    /// Push the height with a trailing zero byte, which does not match `CScript() << height`.
+    pub fn non_minimal_height(block: &mut Block, height: u32) {
+        let mut bytes = Self::scriptint(height);
+        bytes.push(0x00);
+        let push = PushBytesBuf::try_from(bytes).expect("a script number is at most 6 bytes");
+        Self::replace_height(block, Some(Builder::new().push_slice(push).into_script()));
+    }
+
+    /// Push `height + 1` instead of the block height.
+    pub fn wrong_height(block: &mut Block, height: u32) {
+        let wrong = Builder::new().push_int(height as i64 + 1).into_script();
+        Self::replace_height(block, Some(wrong));
+    }
+
+    /// Drop the height push, padding the scriptSig back to its minimum size with `OP_NOP`s.
+    pub fn missing_height(block: &mut Block) {
+        Self::replace_height(block, None);
+    }
+
+    /// Shrink the scriptSig to a single byte.
+    pub fn short_script_sig(block: &mut Block) {
+        Self::set_script_sig(block, |_| vec![OP_NOP.to_u8(); MIN_COINBASE_SCRIPT_SIG - 1]);
+    }
+
+    /// Grow the scriptSig one byte past the limit, keeping the height in front.
+    pub fn long_script_sig(block: &mut Block) {
+        Self::set_script_sig(block, |mut script_sig| {
+            script_sig.resize(MAX_COINBASE_SCRIPT_SIG + 1, OP_NOP.to_u8());
+            script_sig
+        });
+    }
+
+    /// Pay out one satoshi more than the subsidy at `height` plus `fees`, failing when that is
+    /// more than an amount can hold or when the other outputs already pay more.
+    pub fn excessive_value(
+        block: &mut Block,
+        height: u32,
+        fees: Amount,
+    ) -> Result<(), Box<dyn Error>> {
+        let Some(coinbase) = block.txdata.first_mut() else {
+            return Ok(());
+        };
+        let excessive = block_subsidy(height)
+            .checked_add(fees)
+            .and_then(|allowed| allowed.checked_add(Amount::ONE_SAT))
+            .ok_or_else(|| format!("Subsidy plus {} of fees overflows an amount", fees))?;
+        let commitment = witness_commitment_index(coinbase);
+
+        // Adjust the first output that is not the witness commitment, adding one if needed.
+        let index = match (0..coinbase.output.len()).find(|i| Some(*i) != commitment) {
+            Some(index) => index,
+            None => {
+                coinbase.output.insert(
+                    0,
+                    TxOut {
+                        value: Amount::ZERO,
+                        script_pubkey: ScriptBuf::new_op_return([]),
+                    },
+                );
+                0
+            }
+        };
+
+        let others = coinbase
+            .output
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i != index)
+            .map(|(_, output)| output.value)
+            .try_fold(Amount::ZERO, |sum, value| sum.checked_add(value))
+            .ok_or("The other coinbase outputs overflow an amount")?;
+        coinbase.output[index].value = excessive.checked_sub(others).ok_or_else(|| {
+            format!("The other coinbase outputs already pay {}, more than {}", others, excessive)
+        })?;
+        Ok(())
+    }
+
+    /// Give the coinbase input a real looking prevout, so the block has no coinbase at all.
+    pub fn non_null_prevout(block: &mut Block) {
+        if let Some(input) = block.txdata.first_mut().and_then(|tx| tx.input.first_mut()) {
+            input.previous_output.txid = Txid::from_byte_array(rand::rng().random());
+        }
+    }
+
+    /// Append a transaction spending `outpoint`, the block's own coinbase when not given,
+    /// before it reaches the 100 blocks maturity.
+    ///
+    /// A coinbase cannot commit to the wtxid of its own spend, so when spending the block's own
+    /// coinbase the witness commitment is dropped, as long as no other transaction needs it.
+    pub fn spend_immature(block: &mut Block, outpoint: Option<OutPoint>) {
+        let previous_output = match outpoint {
+            Some(outpoint) => outpoint,
+            None => {
+                let needs_commitment = block
+                    .txdata
+                    .iter()
+                    .skip(1)
+                    .any(|tx| tx.input.iter().any(|input| !input.witness.is_empty()));
+                if !needs_commitment {
+                    WitnessCommitmentProcessor::remove_commitment(block);
+                    let coinbase_input = block.txdata.first_mut().and_then(|tx| tx.input.first_mut());
+                    if let Some(input) = coinbase_input {
+                        input.witness = Witness::default();
+                    }
+                }
+
+                let Some(coinbase) = block.txdata.first() else {
+                    return;
+                };
+                OutPoint {
+                    txid: coinbase.compute_txid(),
+                    vout: 0,
+                }
+            }
+        };
+
+        block.txdata.push(Transaction {
+            version: Version::TWO,
+            lock_time: LockTime::ZERO,
+            input: vec![TxIn {
+                previous_output,
+                script_sig: ScriptBuf::new(),
+                sequence: Sequence::MAX,
+                witness: Witness::default(),
+            }],
+            output: vec![TxOut {
+                value: Amount::ZERO,
+                script_pubkey: ScriptBuf::new_op_return([]),
+            }],
+        });
+    }
+
+    /// Minimal script number encoding of `height`.
+    fn scriptint(height: u32) -> Vec<u8> {
+        let mut buf = [0u8; 8];
+        let len = script::write_scriptint(&mut buf, height as i64);
+        buf[..len].to_vec()
+    }
+
+    /// Swap the first push of the scriptSig for `height`, or drop it when `None`.
+    fn replace_height(block: &mut Block, height: Option<ScriptBuf>) {
+        Self::set_script_sig(block, |script_sig| {
+            let script_sig = ScriptBuf::from_bytes(script_sig);
+            let mut builder = Builder::from(height.map(ScriptBuf::into_bytes).unwrap_or_default());
+
+            for instruction in script_sig.instructions().skip(1) {
+                match instruction {
+                    Ok(Instruction::PushBytes(push)) => builder = builder.push_slice(push),
+                    Ok(Instruction::Op(op)) => builder = builder.push_opcode(op),
+                    Err(_) => break,
+                }
+            }
+
+            let mut bytes = builder.into_bytes();
+            if bytes.len() < MIN_COINBASE_SCRIPT_SIG {
+                bytes.resize(MIN_COINBASE_SCRIPT_SIG, OP_NOP.to_u8());
+            }
+            bytes
+        });
+    }
+
+    fn set_script_sig(block: &mut Block, update: impl FnOnce(Vec<u8>) -> Vec<u8>) {
+        if let Some(input) = block.txdata.first_mut().and_then(|tx| tx.input.first_mut()) {
+            input.script_sig = ScriptBuf::from_bytes(update(input.script_sig.to_bytes()));
+        }
+    }
+}
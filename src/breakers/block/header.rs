@@ -1,7 +1,11 @@
+use std::error::Error;
+
 use bitcoin::{
     blockdata::block::{Header, Version}, hashes::{Hash}, pow::CompactTarget
 };
 use bitcoin::blockdata::block::BlockHash;
+use bitcoin::{Network, TxMerkleNode};
+use super::block::{BlockField, ProcessingConfig};
 use super::{version::VersionProcessor, merkle_root::MerkleRootProcessor, bits::BitsProcessor};
 /// Processor for block header modifications
 pub struct HeaderProcessor;
@@ -80,48 +84,98 @@ impl HeaderProcessor {
         version_override: Option<i32>,
         timestamp_offset: Option<i64>,
         randomize_hashes: bool,
-        fields_to_modify: &[super::block::BlockField],
-    ) -> Header {
-        use super::block::BlockField;
-        
-        let mut modified_header = header.clone();
-        let should_modify_all = fields_to_modify.contains(&BlockField::All);
-
-        if should_modify_all || fields_to_modify.contains(&BlockField::Version) {
-            let new_version = VersionProcessor::process_version(
-                header.version.to_consensus(), 
-                version_override
-            );
+        fields_to_modify: &[BlockField],
+    ) -> Result<Header, Box<dyn Error>> {
+        let config = ProcessingConfig {
+            fields_to_modify: fields_to_modify.to_vec(),
+            version_override,
+            timestamp_offset,
+            randomize_hashes,
+            ..Default::default()
+        };
+        Self::process_header_with_config(header, &config)
+    }
+
+    /// Break the selected header fields, using the explicit values and bit flips of `config`
+    /// where given.
+    ///
+    /// Flips are applied on top of the override, or of the original hash when there is none.
+    /// Fails when no version is outdated at `config.coinbase_height`.
+    pub fn process_header_with_config(
+        header: &Header,
+        config: &ProcessingConfig,
+    ) -> Result<Header, Box<dyn Error>> {
+        let fields = &config.fields_to_modify;
+        let mut modified_header = *header;
+        let should_modify_all = fields.contains(&BlockField::All);
+
+        if should_modify_all || fields.contains(&BlockField::Version) {
+            let network = config.network.unwrap_or(Network::Regtest);
+            let height = config.coinbase_height.unwrap_or(u32::MAX);
+            let new_version = match config.version_override {
+                Some(version) => version,
+                None => VersionProcessor::outdated(height, network)?,
+            };
             modified_header.version = Version::from_consensus(new_version);
         }
 
-        if should_modify_all || fields_to_modify.contains(&BlockField::PrevBlockHash) {
-            modified_header.prev_blockhash = Self::process_prev_block_hash(
-                &header.prev_blockhash, 
-                randomize_hashes
-            );
+        if should_modify_all || fields.contains(&BlockField::PrevBlockHash) {
+            let prev_blockhash = match config.prev_blockhash_override {
+                Some(hash) => hash,
+                None if config.prev_blockhash_flips.is_empty() => {
+                    Self::process_prev_block_hash(&header.prev_blockhash, config.randomize_hashes)
+                }
+                None => header.prev_blockhash,
+            };
+            modified_header.prev_blockhash =
+                Self::flip_prev_block_hash(&prev_blockhash, &config.prev_blockhash_flips);
         }
 
-        if should_modify_all || fields_to_modify.contains(&BlockField::MerkleRoot) {
-            modified_header.merkle_root = MerkleRootProcessor::process_merkle_root(
-                &header.merkle_root, 
-                randomize_hashes
-            );
+        if should_modify_all || fields.contains(&BlockField::MerkleRoot) {
+            let merkle_root = match config.merkle_root_override {
+                Some(root) => root,
+                None if config.merkle_root_flips.is_empty() => {
+                    MerkleRootProcessor::process_merkle_root(
+                        &header.merkle_root,
+                        config.randomize_hashes,
+                    )
+                }
+                None => header.merkle_root,
+            };
+            modified_header.merkle_root =
+                MerkleRootProcessor::flip_bits(&merkle_root, &config.merkle_root_flips);
         }
 
-        if should_modify_all || fields_to_modify.contains(&BlockField::Timestamp) {
-            modified_header.time = Self::process_timestamp(header.time, timestamp_offset);
+        if should_modify_all || fields.contains(&BlockField::Timestamp) {
+            modified_header.time = match config.time_override {
+                Some(time) => {
+                    Self::process_timestamp(time, Some(config.timestamp_offset.unwrap_or(0)))
+                }
+                None => Self::process_timestamp(header.time, config.timestamp_offset),
+            };
         }
 
-        if should_modify_all || fields_to_modify.contains(&BlockField::Bits) {
-            let new_bits = BitsProcessor::process_bits(header.bits.to_consensus());
+        if should_modify_all || fields.contains(&BlockField::Bits) {
+            let new_bits = config
+                .bits_override
+                .unwrap_or_else(|| BitsProcessor::process_bits(header.bits.to_consensus()));
             modified_header.bits = CompactTarget::from_consensus(new_bits);
         }
 
-        if should_modify_all || fields_to_modify.contains(&BlockField::Nonce) {
-            modified_header.nonce = Self::process_nonce(header.nonce);
+        if should_modify_all || fields.contains(&BlockField::Nonce) {
+            modified_header.nonce = config
+                .nonce_override
+                .unwrap_or_else(|| Self::process_nonce(header.nonce));
         }
         
-        modified_header
+        Ok(modified_header)
+    }
+
+    /// Flip the given bits of a block hash, counted from the first serialized byte.
+    pub fn flip_prev_block_hash(hash: &BlockHash, bit_positions: &[usize]) -> BlockHash {
+        let node = TxMerkleNode::from_byte_array(hash.to_byte_array());
+        BlockHash::from_byte_array(
+            MerkleRootProcessor::flip_bits(&node, bit_positions).to_byte_array(),
+        )
     }
-}
\ No newline at end of file
+}
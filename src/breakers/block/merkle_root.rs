@@ -1,4 +1,8 @@
-use bitcoin::{hash_types::TxMerkleNode, hashes::Hash};
+use bitcoin::{
+    absolute::LockTime, hash_types::TxMerkleNode, hashes::Hash, opcodes::all::OP_RETURN,
+    transaction::Version, Amount, Block, OutPoint, ScriptBuf, Sequence, Transaction, TxIn, TxOut,
+    Txid, Witness,
+};
 
 pub struct MerkleRootProcessor;
 
@@ -91,4 +95,72 @@ impl MerkleRootProcessor {
         // This is more of a placeholder for future validation logic
         root.as_byte_array().len() == 32
     }
-}
\ No newline at end of file
+
+    /// Lowest merkle tree level holding an odd number of nodes, below the root.
+    ///
+    /// That level's last node is paired with itself, which is what CVE-2012-2459 exploits.
+    pub fn odd_level(tx_count: usize) -> Option<u32> {
+        let mut nodes = tx_count;
+        let mut level = 0;
+
+        while nodes > 1 {
+            if nodes % 2 == 1 {
+                return Some(level);
+            }
+            nodes /= 2;
+            level += 1;
+        }
+
+        None
+    }
+
+    /// Append copies of the transactions under the last node of the first odd level, so the
+    /// merkle root stays the same while the block is mutated (CVE-2012-2459).
+    pub fn duplicate_transactions(block: &Block) -> Result<Block, Box<dyn std::error::Error>> {
+        let level = Self::odd_level(block.txdata.len()).ok_or_else(|| {
+            format!(
+                "A block with {} transactions has no odd merkle level to duplicate",
+                block.txdata.len()
+            )
+        })?;
+
+        // Every level below is even, so the last node covers exactly 2^level transactions.
+        let count = 1usize << level;
+        let mut mutated = block.clone();
+        let tail = block.txdata[block.txdata.len() - count..].to_vec();
+        mutated.txdata.extend(tail);
+
+        Ok(mutated)
+    }
+
+    /// A transaction whose serialization without witness is exactly 64 bytes, the size of an
+    /// inner merkle node, which makes the tree ambiguous.
+    pub fn sixty_four_byte_transaction() -> Transaction {
+        use rand::Rng;
+        let mut rng = rand::rng();
+
+        // 60 bytes of fixed fields, plus a four bytes OP_RETURN output script.
+        let script_pubkey =
+            ScriptBuf::from_bytes(vec![OP_RETURN.to_u8(), 0x02, rng.random(), rng.random()]);
+        let tx = Transaction {
+            version: Version::TWO,
+            lock_time: LockTime::ZERO,
+            input: vec![TxIn {
+                previous_output: OutPoint {
+                    txid: Txid::from_byte_array(rng.random()),
+                    vout: 0,
+                },
+                script_sig: ScriptBuf::new(),
+                sequence: Sequence::MAX,
+                witness: Witness::default(),
+            }],
+            output: vec![TxOut {
+                value: Amount::ZERO,
+                script_pubkey,
+            }],
+        };
+
+        debug_assert_eq!(tx.base_size(), 64);
+        tx
+    }
+}
@@ -2,11 +2,24 @@ pub mod version;
 pub mod header;
 pub mod merkle_root;
 pub mod bits;
+#[allow(clippy::module_inception)]
 pub mod block;
+pub mod chain;
+pub mod coinbase;
 pub mod decoder_tools;
+pub mod pow;
+pub mod signet;
+pub mod timestamp;
+pub mod witness_commitment;
 
 pub use version::VersionProcessor;
 pub use header::HeaderProcessor;
 pub use merkle_root::MerkleRootProcessor;
 pub use bits::BitsProcessor;
+pub use chain::{ChainBreak, ChainProcessor};
+pub use coinbase::CoinbaseProcessor;
+pub use pow::PowProcessor;
+pub use signet::{SignetBreak, SignetProcessor};
+pub use timestamp::{TimestampBreak, TimestampProcessor};
+pub use witness_commitment::WitnessCommitmentProcessor;
 pub use block::{BlockProcessor, BlockBreaker, BlockField, ProcessingConfig};
\ No newline at end of file
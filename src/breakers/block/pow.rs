@@ -0,0 +1,93 @@
+use std::error::Error;
+
+use bitcoin::{block::Header, hashes::Hash, params::Params, pow::CompactTarget, Network, Target};
+
+/// Sign bit of the compact mantissa, marking the target as negative.
+pub const COMPACT_SIGN_BIT: u32 = 0x00800000;
+/// Hashes tried by `PowProcessor::high_hash` before giving up.
+pub const HIGH_HASH_MAX_ATTEMPTS: u64 = 1 << 24;
+
+/// Processor for proof of work modifications
+pub struct PowProcessor;
+
+impl PowProcessor {
+    /// Grind the nonce until the header hash misses its target by at most a factor of two,
+    /// keeping every other field. The time is bumped whenever the nonce space is exhausted.
+    ///
+    /// Costs as much as mining the header, so only practical for easy targets such as regtest's.
+    /// Fails after `HIGH_HASH_MAX_ATTEMPTS` hashes.
+    pub fn high_hash(header: &Header) -> Result<Header, Box<dyn Error>> {
+        let target = header.target();
+        let mut header = *header;
+
+        // Every hash misses a zero target.
+        if target == Target::ZERO {
+            return Ok(header);
+        }
+
+        for _ in 0..HIGH_HASH_MAX_ATTEMPTS {
+            let hash = Target::from_le_bytes(header.block_hash().to_byte_array());
+            if hash > target && Self::halve(hash) <= target {
+                return Ok(header);
+            }
+
+            header.nonce = header.nonce.wrapping_add(1);
+            if header.nonce == 0 {
+                header.time = header.time.wrapping_add(1);
+            }
+        }
+
+        Err(format!(
+            "No hash just above the target of bits {:#010x} within {} attempts",
+            header.bits.to_consensus(),
+            HIGH_HASH_MAX_ATTEMPTS
+        )
+        .into())
+    }
+
+    /// Smallest compact target above the proof of work limit of `network`.
+    pub fn above_pow_limit(network: Network) -> CompactTarget {
+        let limit = Params::new(network).max_attainable_target.to_compact_lossy().to_consensus();
+        let exponent = limit >> 24;
+        let mantissa = limit & 0x007fffff;
+
+        // Past the largest positive mantissa, move on to the next exponent.
+        let bits = if mantissa + 1 >= COMPACT_SIGN_BIT {
+            ((exponent + 1) << 24) | 0x008000
+        } else {
+            (exponent << 24) | (mantissa + 1)
+        };
+        CompactTarget::from_consensus(bits)
+    }
+
+    /// Set the sign bit, keeping a non-zero mantissa so the target decodes as negative.
+    pub fn negative(bits: CompactTarget) -> CompactTarget {
+        let bits = bits.to_consensus();
+        let mantissa = match bits & 0x007fffff {
+            0 => 1,
+            mantissa => mantissa,
+        };
+        CompactTarget::from_consensus((bits & 0xff000000) | COMPACT_SIGN_BIT | mantissa)
+    }
+
+    /// A compact target whose value does not fit in 256 bits.
+    pub fn overflow() -> CompactTarget {
+        CompactTarget::from_consensus(0x23000001)
+    }
+
+    /// A compact target decoding to zero, which no hash can meet.
+    pub fn zero() -> CompactTarget {
+        CompactTarget::from_consensus(0x01003456)
+    }
+
+    fn halve(value: Target) -> Target {
+        let mut bytes = value.to_le_bytes();
+        let mut carry = 0u8;
+        for byte in bytes.iter_mut().rev() {
+            let next_carry = *byte & 1;
+            *byte = (*byte >> 1) | (carry << 7);
+            carry = next_carry;
+        }
+        Target::from_le_bytes(bytes)
+    }
+}
@@ -0,0 +1,126 @@
+use std::error::Error;
+use std::str::FromStr;
+
+use bitcoin::{
+    consensus::{encode, Encodable},
+    script::{Builder, Instruction, PushBytesBuf},
+    secp256k1::Secp256k1,
+    Block, NetworkKind, PrivateKey, PublicKey, ScriptBuf, Witness,
+};
+
+use crate::block::{generator::GenerateBlock, signet::Signet};
+
+/// Ways of corrupting the signet solution of a block, all rejected as `bad-signet-blksig`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SignetBreak {
+    /// Remove the solution from the coinbase commitment.
+    MissingSolution,
+    /// Flip a byte inside the first signature of the solution.
+    CorruptSignature,
+    /// Sign the block for a challenge of the same shape over an unrelated key.
+    WrongKey,
+    /// Append bytes after the witness stack of the solution.
+    TrailingData,
+    /// Change the header time after signing, so the solution commits to another block.
+    StaleSolution,
+}
+
+impl FromStr for SignetBreak {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "missing" | "missing-solution" => Ok(Self::MissingSolution),
+            "bad-sig" | "corrupt-signature" => Ok(Self::CorruptSignature),
+            "wrong-key" => Ok(Self::WrongKey),
+            "trailing-data" => Ok(Self::TrailingData),
+            "stale" | "stale-solution" => Ok(Self::StaleSolution),
+            _ => Err(format!("Unknown signet break mode '{}'", s)),
+        }
+    }
+}
+
+/// Processor for signet block solution modifications
+pub struct SignetProcessor;
+
+impl SignetProcessor {
+    /// Corrupt the signet solution of `block` and mine it again against its own bits.
+    pub fn process_signet(
+        block: &Block,
+        mode: SignetBreak,
+        challenge: &ScriptBuf,
+    ) -> Result<Block, Box<dyn Error>> {
+        let mut broken = block.clone();
+        let solution = Signet::solution(block).ok_or("Block carries no signet solution")?;
+
+        match mode {
+            SignetBreak::MissingSolution => Signet::set_solution(&mut broken, None)?,
+            SignetBreak::CorruptSignature => {
+                let (script_sig, witness) = Signet::decode_solution(&solution)?;
+                let (script_sig, witness) = Self::corrupt_first_signature(&script_sig, &witness)
+                    .ok_or("Signet solution holds no signature to corrupt")?;
+
+                let mut corrupted = encode::serialize(&script_sig);
+                witness.consensus_encode(&mut corrupted)?;
+                Signet::set_solution(&mut broken, Some(corrupted))?;
+            }
+            SignetBreak::WrongKey => {
+                let stranger = PrivateKey::generate(NetworkKind::Test);
+                let secp = Secp256k1::new();
+                let pubkey = PublicKey::from_private_key(&secp, &stranger);
+
+                let stranger_challenge = if challenge.is_p2wpkh() {
+                    ScriptBuf::new_p2wpkh(&pubkey.wpubkey_hash()?)
+                } else if challenge.is_p2tr() {
+                    ScriptBuf::new_p2tr(&secp, pubkey.inner.x_only_public_key().0, None)
+                } else {
+                    Signet::multisig_challenge(&[stranger])
+                };
+                Signet::sign(&mut broken, &stranger_challenge, &[stranger])?;
+            }
+            SignetBreak::TrailingData => {
+                let mut extended = solution.clone();
+                extended.push(0x00);
+                Signet::set_solution(&mut broken, Some(extended))?;
+            }
+            SignetBreak::StaleSolution => {
+                broken.header.time = broken.header.time.wrapping_add(1);
+            }
+        }
+
+        broken.header = GenerateBlock::mine(broken.header)?;
+        Ok(broken)
+    }
+
+    /// Flip a byte of the first signature-sized element, in the scriptSig or the witness.
+    fn corrupt_first_signature(
+        script_sig: &ScriptBuf,
+        witness: &Witness,
+    ) -> Option<(ScriptBuf, Witness)> {
+        // Byte 10 lies inside R for both DER and BIP340 encodings, keeping the encoding valid.
+        const CORRUPTED_BYTE: usize = 10;
+
+        let mut items = witness.to_vec();
+        if let Some(item) = items.iter_mut().find(|item| item.len() >= 64) {
+            item[CORRUPTED_BYTE] ^= 0x01;
+            return Some((script_sig.clone(), Witness::from_slice(&items)));
+        }
+
+        let mut builder = Builder::new();
+        let mut corrupted = false;
+        for instruction in script_sig.instructions() {
+            match instruction.ok()? {
+                Instruction::PushBytes(push) if !corrupted && push.len() >= 64 => {
+                    let mut bytes = push.as_bytes().to_vec();
+                    bytes[CORRUPTED_BYTE] ^= 0x01;
+                    builder = builder.push_slice(PushBytesBuf::try_from(bytes).ok()?);
+                    corrupted = true;
+                }
+                Instruction::PushBytes(push) => builder = builder.push_slice(push),
+                Instruction::Op(op) => builder = builder.push_opcode(op),
+            }
+        }
+
+        corrupted.then(|| (builder.into_script(), witness.clone()))
+    }
+}
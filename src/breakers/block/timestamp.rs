@@ -0,0 +1,104 @@
+use std::error::Error;
+use std::str::FromStr;
+
+use bitcoin::{block::Header, hashes::Hash, params::Params, Network, TxMerkleNode};
+use rand::Rng;
+
+use crate::block::generator::GenerateBlock;
+use crate::block::random::chain::HeaderChain;
+use crate::block::random::timestamp::{Timestamp, MAX_FUTURE_BLOCK_TIME};
+
+/// How far the first block of a retarget period may go back from its parent (BIP94).
+pub const MAX_TIMEWARP: u32 = 600;
+
+/// Timestamps rejected relative to the ancestors or the network-adjusted time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TimestampBreak {
+    /// Exactly the median time past, rejected as `time-too-old`.
+    MedianTimePast,
+    /// One second below the median time past, also `time-too-old`.
+    BelowMedianTimePast,
+    /// One second past the two hours future limit, rejected as `time-too-new`.
+    TooNew,
+    /// One second more than `MAX_TIMEWARP` before the parent, rejected as `time-timewarp-attack`
+    /// on the first block of a retarget period.
+    Timewarp,
+}
+
+impl FromStr for TimestampBreak {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "mtp" | "median-time-past" => Ok(Self::MedianTimePast),
+            "below-mtp" => Ok(Self::BelowMedianTimePast),
+            "too-new" | "future" => Ok(Self::TooNew),
+            "timewarp" => Ok(Self::Timewarp),
+            _ => Err(format!("Unknown timestamp break mode '{}'", s)),
+        }
+    }
+}
+
+/// Processor for timestamp modifications checked against the chain
+pub struct TimestampProcessor;
+
+impl TimestampProcessor {
+    /// Broken timestamp for a block on top of `prev_times`, oldest first.
+    ///
+    /// `adjusted_time` stands for the network-adjusted time, the system time when not given.
+    /// Returns `None` when the rule needs ancestors and `prev_times` is empty.
+    pub fn broken_time(
+        prev_times: &[u32],
+        mode: TimestampBreak,
+        adjusted_time: Option<u32>,
+    ) -> Option<u32> {
+        match mode {
+            TimestampBreak::MedianTimePast => Timestamp::median_time_past(prev_times),
+            TimestampBreak::BelowMedianTimePast => {
+                Timestamp::median_time_past(prev_times).map(|median| median.saturating_sub(1))
+            }
+            TimestampBreak::TooNew => {
+                Some(Self::too_new(adjusted_time.unwrap_or_else(Timestamp::now)))
+            }
+            TimestampBreak::Timewarp => prev_times.last().map(|&prev| Self::timewarp(prev)),
+        }
+    }
+
+    /// First timestamp past the future limit of `adjusted_time`.
+    pub fn too_new(adjusted_time: u32) -> u32 {
+        adjusted_time.saturating_add(MAX_FUTURE_BLOCK_TIME + 1)
+    }
+
+    /// Latest timestamp rejected for the first block of a retarget period, given its parent's.
+    pub fn timewarp(prev_time: u32) -> u32 {
+        prev_time.saturating_sub(MAX_TIMEWARP + 1)
+    }
+
+    /// Whether a block at `height` starts a retarget period on `network`, where BIP94 applies.
+    pub fn is_retarget_height(height: u32, network: Network) -> bool {
+        (height as u64).is_multiple_of(Params::new(network).difficulty_adjustment_interval())
+    }
+
+    /// A mined header extending `chain` whose timestamp breaks `mode`.
+    ///
+    /// For `Timewarp` the chain has to end right before a retarget height for the header to
+    /// be rejected, see `is_retarget_height`.
+    pub fn next_header(
+        chain: &HeaderChain,
+        mode: TimestampBreak,
+        adjusted_time: Option<u32>,
+    ) -> Result<Header, Box<dyn Error>> {
+        let tip = chain.tip().ok_or("Header chain is empty")?;
+        let time = Self::broken_time(&chain.times(), mode, adjusted_time)
+            .ok_or("Header chain has no timestamps")?;
+
+        GenerateBlock::mine(Header {
+            version: tip.version,
+            prev_blockhash: tip.block_hash(),
+            merkle_root: TxMerkleNode::from_byte_array(rand::rng().random()),
+            time,
+            bits: tip.bits,
+            nonce: 0,
+        })
+    }
+}
@@ -1,16 +1,40 @@
+use std::error::Error;
+
 use bitcoin::blockdata::block::Version;
+use bitcoin::Network;
+
+use crate::block::random::version::{VersionBits, VERSIONBITS_NUM_BITS, VERSIONBITS_TOP_BITS};
+
+/// Version bits used by past and test deployments: csv, segwit, taproot and testdummy.
+pub const KNOWN_DEPLOYMENT_BITS: [u8; 4] = [0, 1, 2, 28];
 
 pub struct VersionProcessor;
 
 impl VersionProcessor {
     /// Process the version of the block with optional override
     pub fn process_version(_version: i32, version_override: Option<i32>) -> i32 {
-        if let Some(override_version) = version_override {
-            override_version
-        } else {
-            // Default behavior: set version to maximum valid value
-            0x3FFFFFFF
-        }
+        // Default behavior: one below the version required once BIP34, BIP66 and BIP65 are active
+        version_override.unwrap_or_else(|| VersionBits::minimum(u32::MAX, Network::Regtest) - 1)
+    }
+
+    /// Highest version rejected as `bad-version` at `height` on `network`.
+    ///
+    /// Fails before BIP34, where every version is accepted.
+    pub fn outdated(height: u32, network: Network) -> Result<i32, Box<dyn Error>> {
+        VersionBits::outdated(height, network).ok_or_else(|| {
+            format!("Every version is accepted at height {} on {}", height, network).into()
+        })
+    }
+
+    /// A BIP9 version signalling a random bit no deployment has used, which nodes only warn
+    /// about as unknown rules once it locks in.
+    pub fn unknown_deployment() -> i32 {
+        use rand::Rng;
+        let unused: Vec<u8> = (0..VERSIONBITS_NUM_BITS)
+            .filter(|bit| !KNOWN_DEPLOYMENT_BITS.contains(bit))
+            .collect();
+        let bit = unused[rand::rng().random_range(0..unused.len())];
+        VERSIONBITS_TOP_BITS | (1 << bit)
     }
 
     /// Convert consensus version to Bitcoin Version type
@@ -0,0 +1,141 @@
+use bitcoin::{
+    absolute::LockTime, hashes::Hash, transaction::Version, Amount, Block, OutPoint, ScriptBuf,
+    Sequence, Transaction, TxIn, TxOut, Txid, Witness, WitnessCommitment,
+};
+use rand::Rng;
+
+use crate::transaction::random::coinbase::{witness_commitment_index, witness_commitment_script};
+
+/// Processor for segwit commitment modifications of the coinbase (BIP141)
+pub struct WitnessCommitmentProcessor;
+
+impl WitnessCommitmentProcessor {
+    /// Drop the commitment outputs while the coinbase keeps its witness reserved value.
+    pub fn remove_commitment(block: &mut Block) {
+        if let Some(coinbase) = block.txdata.first_mut() {
+            while let Some(index) = witness_commitment_index(coinbase) {
+                coinbase.output.remove(index);
+            }
+        }
+    }
+
+    /// Flip a byte of the committed hash, adding a commitment first when there is none.
+    pub fn corrupt_commitment(block: &mut Block) {
+        let Some(coinbase) = block.txdata.first_mut() else {
+            return;
+        };
+
+        match witness_commitment_index(coinbase) {
+            Some(index) => {
+                let mut script = coinbase.output[index].script_pubkey.to_bytes();
+                // Skip OP_RETURN, the push opcode and the commitment header.
+                script[6] ^= 0x01;
+                coinbase.output[index].script_pubkey = ScriptBuf::from_bytes(script);
+            }
+            None => {
+                Self::ensure_reserved_value(coinbase);
+                coinbase.output.push(Self::random_commitment_output());
+            }
+        }
+    }
+
+    /// Move the commitment out of the coinbase into the last transaction of the block,
+    /// appending a transaction to hold it when the block only has a coinbase.
+    pub fn misplace_commitment(block: &mut Block) {
+        let Some(index) = block.txdata.first().and_then(witness_commitment_index) else {
+            return;
+        };
+
+        let commitment = block.txdata[0].output.remove(index);
+        if block.txdata.len() == 1 {
+            block.txdata.push(Self::holder_transaction(commitment));
+        } else if let Some(last) = block.txdata.last_mut() {
+            last.output.push(commitment);
+        }
+    }
+
+    /// Append a second commitment with a random hash, the last one being the one checked.
+    pub fn add_second_commitment(block: &mut Block) {
+        if let Some(coinbase) = block.txdata.first_mut() {
+            Self::ensure_reserved_value(coinbase);
+            coinbase.output.push(Self::random_commitment_output());
+        }
+    }
+
+    /// Make the coinbase witness reserved value one byte longer than the required 32.
+    pub fn resize_reserved_value(block: &mut Block) {
+        let Some(input) = block.txdata.first_mut().and_then(|tx| tx.input.first_mut()) else {
+            return;
+        };
+
+        let mut reserved_value = input.witness.nth(0).map(<[u8]>::to_vec).unwrap_or_default();
+        reserved_value.resize(33, 0);
+        input.witness = Witness::from_slice(&[reserved_value]);
+    }
+
+    /// Strip every commitment and the reserved value, making sure some transaction still
+    /// carries witness data.
+    pub fn unexpected_witness(block: &mut Block) {
+        Self::remove_commitment(block);
+
+        if let Some(input) = block.txdata.first_mut().and_then(|tx| tx.input.first_mut()) {
+            input.witness = Witness::default();
+        }
+
+        let has_witness = block
+            .txdata
+            .iter()
+            .skip(1)
+            .any(|tx| tx.input.iter().any(|input| !input.witness.is_empty()));
+        if has_witness {
+            return;
+        }
+
+        match block.txdata.get_mut(1).and_then(|tx| tx.input.first_mut()) {
+            Some(input) => input.witness = Witness::from_slice(&[vec![0x01]]),
+            None => {
+                let mut holder = Self::holder_transaction(TxOut {
+                    value: Amount::ZERO,
+                    script_pubkey: ScriptBuf::new_op_return([]),
+                });
+                holder.input[0].witness = Witness::from_slice(&[vec![0x01]]);
+                block.txdata.push(holder);
+            }
+        }
+    }
+
+    /// Give the coinbase the single 32 bytes witness item a commitment requires.
+    fn ensure_reserved_value(coinbase: &mut Transaction) {
+        if let Some(input) = coinbase.input.first_mut() {
+            if input.witness.is_empty() {
+                input.witness = Witness::from_slice(&[[0u8; 32]]);
+            }
+        }
+    }
+
+    fn random_commitment_output() -> TxOut {
+        let commitment = WitnessCommitment::from_byte_array(rand::rng().random());
+        TxOut {
+            value: Amount::ZERO,
+            script_pubkey: witness_commitment_script(&commitment),
+        }
+    }
+
+    /// A non-coinbase transaction paying to `output`, spending a random outpoint.
+    fn holder_transaction(output: TxOut) -> Transaction {
+        Transaction {
+            version: Version::TWO,
+            lock_time: LockTime::ZERO,
+            input: vec![TxIn {
+                previous_output: OutPoint {
+                    txid: Txid::from_byte_array(rand::rng().random()),
+                    vout: 0,
+                },
+                script_sig: ScriptBuf::new(),
+                sequence: Sequence::MAX,
+                witness: Witness::default(),
+            }],
+            output: vec![output],
+        }
+    }
+}
@@ -1,3 +1,4 @@
 pub mod decoder_tools;
+pub mod raw;
 pub mod transaction;
 pub mod block;
\ No newline at end of file
@@ -0,0 +1,248 @@
+use std::error::Error;
+use std::str::FromStr;
+
+/// Size of a serialized block header.
+pub const HEADER_SIZE: usize = 80;
+
+/// A field of a serialized transaction or block, as the byte range it covers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RawField {
+    /// Path of the field, e.g. `input[0].script_sig` or `tx[1].locktime`.
+    pub name: String,
+    pub start: usize,
+    pub end: usize,
+}
+
+impl RawField {
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+}
+
+/// Field boundaries of a serialized transaction or block, in encoding order.
+#[derive(Debug, Clone, Default)]
+pub struct ParseTrace {
+    pub fields: Vec<RawField>,
+}
+
+impl ParseTrace {
+    /// Trace a serialized transaction, segwit or legacy.
+    pub fn transaction(bytes: &[u8]) -> Result<Self, Box<dyn Error>> {
+        let mut tracer = Tracer::new(bytes);
+        tracer.transaction("")?;
+        tracer.finish()
+    }
+
+    /// Trace a serialized block, the header followed by its transactions.
+    pub fn block(bytes: &[u8]) -> Result<Self, Box<dyn Error>> {
+        let mut tracer = Tracer::new(bytes);
+        for (name, size) in [
+            ("header.version", 4),
+            ("header.prev_blockhash", 32),
+            ("header.merkle_root", 32),
+            ("header.time", 4),
+            ("header.bits", 4),
+            ("header.nonce", 4),
+        ] {
+            tracer.field(name, size)?;
+        }
+        let count = tracer.varint("tx_count")?;
+        for index in 0..count {
+            tracer.transaction(&format!("tx[{}].", index))?;
+        }
+        tracer.finish()
+    }
+
+    pub fn field(&self, name: &str) -> Option<&RawField> {
+        self.fields.iter().find(|field| field.name == name)
+    }
+}
+
+/// Byte level changes to a single field.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum RawMutation {
+    /// Invert every bit of the field.
+    Flip,
+    /// End the data halfway through the field.
+    Truncate,
+    /// Insert bytes right after the field.
+    Extend(Vec<u8>),
+    /// Put other bytes, of any length, in place of the field.
+    Replace(Vec<u8>),
+}
+
+impl FromStr for RawMutation {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, bytes) = match s.split_once('=') {
+            Some((name, hex)) => {
+                let bytes = hex::decode(hex.trim_start_matches("0x"))
+                    .map_err(|e| format!("Invalid bytes '{}': {}", hex, e))?;
+                (name, Some(bytes))
+            }
+            None => (s, None),
+        };
+
+        match (name.to_lowercase().as_str(), bytes) {
+            ("flip", None) => Ok(Self::Flip),
+            ("truncate", None) => Ok(Self::Truncate),
+            ("extend", Some(bytes)) => Ok(Self::Extend(bytes)),
+            ("replace", Some(bytes)) => Ok(Self::Replace(bytes)),
+            _ => Err(format!(
+                "Unknown raw mutation '{}', expected flip, truncate, extend=<hex> or replace=<hex>",
+                s
+            )),
+        }
+    }
+}
+
+/// Processor for mutations of serialized data, guided by a `ParseTrace`
+pub struct RawMutator;
+
+impl RawMutator {
+    /// Apply `mutation` to the field called `name` of `bytes`.
+    pub fn mutate(
+        bytes: &[u8],
+        trace: &ParseTrace,
+        name: &str,
+        mutation: &RawMutation,
+    ) -> Result<Vec<u8>, Box<dyn Error>> {
+        let field = trace.field(name).ok_or_else(|| {
+            format!("Unknown field '{}', see the parse trace for field names", name)
+        })?;
+        Ok(Self::mutate_field(bytes, field, mutation))
+    }
+
+    /// Apply `mutation` to the bytes covered by `field`.
+    pub fn mutate_field(bytes: &[u8], field: &RawField, mutation: &RawMutation) -> Vec<u8> {
+        let (before, rest) = bytes.split_at(field.start);
+        let (content, after) = rest.split_at(field.len());
+
+        match mutation {
+            RawMutation::Flip => {
+                let flipped: Vec<u8> = content.iter().map(|byte| !byte).collect();
+                [before, &flipped, after].concat()
+            }
+            RawMutation::Truncate => [before, &content[..field.len() / 2]].concat(),
+            RawMutation::Extend(extra) => [before, content, extra, after].concat(),
+            RawMutation::Replace(replacement) => [before, replacement, after].concat(),
+        }
+    }
+}
+
+/// Cursor recording each field it reads.
+struct Tracer<'a> {
+    bytes: &'a [u8],
+    position: usize,
+    fields: Vec<RawField>,
+}
+
+impl<'a> Tracer<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self {
+            bytes,
+            position: 0,
+            fields: Vec::new(),
+        }
+    }
+
+    fn finish(self) -> Result<ParseTrace, Box<dyn Error>> {
+        if self.position != self.bytes.len() {
+            return Err(format!(
+                "{} bytes left after the last field",
+                self.bytes.len() - self.position
+            )
+            .into());
+        }
+        Ok(ParseTrace { fields: self.fields })
+    }
+
+    fn field(&mut self, name: &str, size: usize) -> Result<&'a [u8], Box<dyn Error>> {
+        let start = self.position;
+        let end = start
+            .checked_add(size)
+            .filter(|end| *end <= self.bytes.len())
+            .ok_or_else(|| format!("Unexpected end of data reading {}", name))?;
+
+        self.fields.push(RawField {
+            name: name.to_string(),
+            start,
+            end,
+        });
+        self.position = end;
+        Ok(&self.bytes[start..end])
+    }
+
+    /// Read a CompactSize, canonical or not.
+    fn varint(&mut self, name: &str) -> Result<u64, Box<dyn Error>> {
+        let size = match self.bytes.get(self.position) {
+            Some(0xfd) => 3,
+            Some(0xfe) => 5,
+            Some(0xff) => 9,
+            Some(_) => 1,
+            None => return Err(format!("Unexpected end of data reading {}", name).into()),
+        };
+        let bytes = self.field(name, size)?;
+        if size == 1 {
+            return Ok(bytes[0] as u64);
+        }
+
+        let mut value = [0u8; 8];
+        value[..size - 1].copy_from_slice(&bytes[1..]);
+        Ok(u64::from_le_bytes(value))
+    }
+
+    /// Read a CompactSize length, then as many bytes.
+    fn var_bytes(&mut self, name: &str) -> Result<(), Box<dyn Error>> {
+        let len = self.varint(&format!("{}_len", name))?;
+        let len = usize::try_from(len).map_err(|_| format!("{} is too long", name))?;
+        self.field(name, len)?;
+        Ok(())
+    }
+
+    fn transaction(&mut self, prefix: &str) -> Result<(), Box<dyn Error>> {
+        self.field(&format!("{}version", prefix), 4)?;
+
+        // A zero input count followed by a non-zero byte is the segwit marker and flag
+        let has_witness = self.bytes.get(self.position) == Some(&0x00)
+            && self.bytes.get(self.position + 1).is_some_and(|flag| *flag != 0x00);
+        if has_witness {
+            self.field(&format!("{}marker", prefix), 1)?;
+            self.field(&format!("{}flag", prefix), 1)?;
+        }
+
+        let inputs = self.varint(&format!("{}input_count", prefix))?;
+        for index in 0..inputs {
+            let input = format!("{}input[{}]", prefix, index);
+            self.field(&format!("{}.txid", input), 32)?;
+            self.field(&format!("{}.vout", input), 4)?;
+            self.var_bytes(&format!("{}.script_sig", input))?;
+            self.field(&format!("{}.sequence", input), 4)?;
+        }
+
+        let outputs = self.varint(&format!("{}output_count", prefix))?;
+        for index in 0..outputs {
+            let output = format!("{}output[{}]", prefix, index);
+            self.field(&format!("{}.amount", output), 8)?;
+            self.var_bytes(&format!("{}.script_pubkey", output))?;
+        }
+
+        if has_witness {
+            for index in 0..inputs {
+                let witness = format!("{}witness[{}]", prefix, index);
+                let items = self.varint(&format!("{}.count", witness))?;
+                for item in 0..items {
+                    self.var_bytes(&format!("{}[{}]", witness, item))?;
+                }
+            }
+        }
+
+        self.field(&format!("{}locktime", prefix), 4)?;
+        Ok(())
+    }
+}
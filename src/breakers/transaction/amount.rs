@@ -0,0 +1,123 @@
+use std::error::Error;
+use std::str::FromStr;
+
+use bitcoin::{Amount, ScriptBuf, Transaction, TxOut};
+
+/// Sign bit of the 64-bit value field, read as a signed integer by nodes.
+pub const AMOUNT_SIGN_BIT: u64 = 1 << 63;
+
+/// Output value corruptions, each with the rejection it is expected to raise.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AmountBreak {
+    /// Exactly `MAX_MONEY`, the largest value an output may have.
+    MaxMoney,
+    /// One satoshi above `MAX_MONEY` (`bad-txns-vout-toolarge`).
+    AboveMaxMoney,
+    /// Outputs of `MAX_MONEY` each, valid alone but with a total above it
+    /// (`bad-txns-txouttotal-toolarge`).
+    TotalOverflow,
+    /// Set the sign bit of the value (`bad-txns-vout-negative`).
+    Negative,
+    /// A zero value, dust unless the output is `OP_RETURN` (policy).
+    Zero,
+    /// One satoshi below the dust threshold of the output script (policy).
+    Dust,
+    /// Outputs paying one satoshi more than the prevout values (`bad-txns-in-belowout`).
+    ExceedInputs,
+}
+
+impl FromStr for AmountBreak {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "max-money" => Ok(Self::MaxMoney),
+            "above-max-money" => Ok(Self::AboveMaxMoney),
+            "total-overflow" | "overflow" => Ok(Self::TotalOverflow),
+            "negative" => Ok(Self::Negative),
+            "zero" => Ok(Self::Zero),
+            "dust" => Ok(Self::Dust),
+            "exceed-inputs" => Ok(Self::ExceedInputs),
+            _ => Err(format!("Unknown amount break mode '{}'", s)),
+        }
+    }
+}
+
+/// Apply an output level `mode` to `output`, transaction level modes are left to
+/// `overflow_total` and `exceed_inputs`.
+pub fn break_amount(output: &mut TxOut, mode: AmountBreak) {
+    output.value = match mode {
+        AmountBreak::MaxMoney => Amount::MAX_MONEY,
+        AmountBreak::AboveMaxMoney => Amount::MAX_MONEY + Amount::ONE_SAT,
+        AmountBreak::Negative => Amount::from_sat(output.value.to_sat() | AMOUNT_SIGN_BIT),
+        AmountBreak::Zero => Amount::ZERO,
+        AmountBreak::Dust => {
+            let threshold = output.script_pubkey.minimal_non_dust();
+            threshold.checked_sub(Amount::ONE_SAT).unwrap_or(Amount::ZERO)
+        }
+        AmountBreak::TotalOverflow | AmountBreak::ExceedInputs => return,
+    };
+}
+
+/// Set the outputs at `indices`, every output when empty, to `MAX_MONEY`, cloning the last
+/// output first when fewer than two would be set.
+pub fn overflow_total(tx: &mut Transaction, indices: &[usize]) {
+    let mut selected: Vec<usize> = (0..tx.output.len())
+        .filter(|index| indices.is_empty() || indices.contains(index))
+        .collect();
+    while selected.len() < 2 {
+        let output = tx.output.last().cloned().unwrap_or(TxOut {
+            value: Amount::ZERO,
+            script_pubkey: ScriptBuf::new(),
+        });
+        tx.output.push(output);
+        selected.push(tx.output.len() - 1);
+    }
+
+    for index in selected {
+        tx.output[index].value = Amount::MAX_MONEY;
+    }
+}
+
+/// Adjust the first output at `indices`, the last output when empty, so the outputs pay one
+/// satoshi more than `prevout_values`.
+///
+/// When the other outputs alone already exceed the inputs, the adjusted output is set to zero.
+/// Fails without prevout values or such an output, or when the values add up to more than an
+/// amount can hold.
+pub fn exceed_inputs(
+    tx: &mut Transaction,
+    prevout_values: &[Amount],
+    indices: &[usize],
+) -> Result<(), Box<dyn Error>> {
+    if prevout_values.is_empty() {
+        return Err("Exceeding the inputs needs the prevout values, see --prevout-value".into());
+    }
+    let index = match indices.first() {
+        Some(index) => *index,
+        None => tx.output.len().saturating_sub(1),
+    };
+    if index >= tx.output.len() {
+        return Err(format!(
+            "No output {} to exceed the inputs with, the transaction has {}",
+            index,
+            tx.output.len()
+        )
+        .into());
+    }
+
+    let exceeding = prevout_values
+        .iter()
+        .try_fold(Amount::ONE_SAT, |sum, value| sum.checked_add(*value))
+        .ok_or("The prevout values overflow an amount")?;
+    let others = tx
+        .output
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| *i != index)
+        .try_fold(Amount::ZERO, |sum, (_, output)| sum.checked_add(output.value))
+        .ok_or("The other output values overflow an amount")?;
+
+    tx.output[index].value = exceeding.checked_sub(others).unwrap_or(Amount::ZERO);
+    Ok(())
+}
@@ -1,5 +1,14 @@
+use super::amount::AmountBreak;
+use super::locktime::FinalityBreak;
+use super::script::ScriptBreak;
+use super::serialization::SerializationBreak;
+use super::signature::SignatureBreak;
+use super::structure::StructureBreak;
+use super::version::VersionBreak;
+use super::witness::WitnessBreak;
+
 // Define available invalidation flags
-#[derive(Debug, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum InvalidationFlag {
     Version,    
     InputTxid,
@@ -11,9 +20,28 @@ pub enum InvalidationFlag {
     WitnessData,
     Locktime,
     All,
+    /// Break the first signature of each targeted input, not part of `All`.
+    Signature(SignatureBreak),
+    /// Mutate the scriptSig of each targeted input, not part of `All`.
+    InputScript(ScriptBreak),
+    /// Mutate the scriptPubKey of each targeted output, not part of `All`.
+    OutputScript(ScriptBreak),
+    /// Manipulate the witness stack of each targeted input, not part of `All`.
+    Witness(WitnessBreak),
+    /// Corrupt the value of each targeted output, not part of `All`.
+    Amount(AmountBreak),
+    /// Change the shape of the transaction, not part of `All`.
+    Structure(StructureBreak),
+    /// Corrupt the encoding of the transaction, only visible in the raw hex, not part of `All`.
+    Serialization(SerializationBreak),
+    /// Break lock time and sequence rules against the chain tip, not part of `All`.
+    Finality(FinalityBreak),
+    /// Set the transaction version for a given rule, not part of `All`.
+    TxVersion(VersionBreak),
 }
 
 impl InvalidationFlag {
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(s: &str) -> Option<Self> {
         match s.to_lowercase().as_str() {
             "version" => Some(Self::Version),
@@ -26,7 +54,23 @@ impl InvalidationFlag {
             "witness" | "witness-data" => Some(Self::WitnessData),
             "locktime" => Some(Self::Locktime),
             "all" => Some(Self::All),
-            _ => None,
+            flag => {
+                let (name, mode) = flag.split_once('=')?;
+                match name {
+                    "sig" | "signature" => mode.parse().ok().map(Self::Signature),
+                    "script-sig" | "input-script" => mode.parse().ok().map(Self::InputScript),
+                    "script-pubkey" | "output-script" => {
+                        mode.parse().ok().map(Self::OutputScript)
+                    }
+                    "witness" => mode.parse().ok().map(Self::Witness),
+                    "amount" => mode.parse().ok().map(Self::Amount),
+                    "structure" => mode.parse().ok().map(Self::Structure),
+                    "serialization" => mode.parse().ok().map(Self::Serialization),
+                    "finality" => mode.parse().ok().map(Self::Finality),
+                    "version" => mode.parse().ok().map(Self::TxVersion),
+                    _ => None,
+                }
+            }
         }
     }
 }
\ No newline at end of file
@@ -1,6 +1,11 @@
 use std::collections::HashSet;
 use bitcoin::{TxIn, Witness};
-use super::{InvalidationFlag, script::corrupt_script};
+use super::{
+    InvalidationFlag,
+    script::{corrupt_script, mutate_script},
+    signature::SignatureProcessor,
+    witness::break_witness,
+};
 
 pub fn invalidate_input_in_place(
     input: &mut TxIn, 
@@ -27,6 +32,23 @@ pub fn invalidate_input_in_place(
     if invalidate_all || flags.contains(&InvalidationFlag::WitnessData) {
         input.witness = corrupt_witness(&input.witness);
     }
+
+    // Flags carrying a mode, inputs without a matching signature or a witness the mode
+    // applies to are left as they are
+    for flag in flags {
+        match flag {
+            InvalidationFlag::Signature(mode) => {
+                SignatureProcessor::break_input(input, *mode);
+            }
+            InvalidationFlag::InputScript(mode) => {
+                input.script_sig = mutate_script(&input.script_sig, *mode);
+            }
+            InvalidationFlag::Witness(mode) => {
+                break_witness(input, *mode);
+            }
+            _ => {}
+        }
+    }
 }
 
 pub fn corrupt_witness(witness: &Witness) -> Witness {
@@ -1,4 +1,15 @@
-use bitcoin::absolute::LockTime;
+use std::error::Error;
+use std::str::FromStr;
+
+use bitcoin::absolute::{LockTime, LOCK_TIME_THRESHOLD};
+use bitcoin::opcodes::all::{OP_CLTV, OP_CSV, OP_DROP};
+use bitcoin::opcodes::Opcode;
+use bitcoin::script::{Builder, Instruction};
+use bitcoin::transaction::Version;
+use bitcoin::{Script, ScriptBuf, Sequence, Transaction, TxIn};
+
+use super::transaction::InvalidationConfig;
+use crate::block::random::timestamp::Timestamp;
 
 pub fn invalidate_locktime(lt: LockTime) -> LockTime {
     match lt {
@@ -17,4 +28,179 @@ pub fn invalidate_locktime(lt: LockTime) -> LockTime {
             )
         },
     }
-}
\ No newline at end of file
+}
+
+/// Relative lock time type flag of BIP68 sequences and BIP112 arguments, set for time locks.
+pub const SEQUENCE_LOCKTIME_TYPE_FLAG: u32 = 1 << 22;
+
+/// Lock time rules broken against a chain tip, each with the rejection it is expected to raise.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FinalityBreak {
+    /// Lock until one block past the tip with non-final sequences (`bad-txns-nonfinal`), needs
+    /// the tip height.
+    HeightPastTip,
+    /// Lock until one second past the tip median time past with non-final sequences (BIP113,
+    /// `bad-txns-nonfinal`).
+    TimePastTip,
+    /// Make every sequence final so the lock time is ignored, failing any
+    /// `OP_CHECKLOCKTIMEVERIFY` (`UNSATISFIED_LOCKTIME`).
+    FinalSequences,
+    /// Relative lock one block more than the prevout confirmations (BIP68, `non-BIP68-final`).
+    RelativeLockUnmet,
+    /// Lock time of the other type than the `OP_CHECKLOCKTIMEVERIFY` argument, already reached
+    /// so the transaction stays final (`UNSATISFIED_LOCKTIME`).
+    CltvTypeMismatch,
+    /// Sequence of the other type than the `OP_CHECKSEQUENCEVERIFY` argument, of zero so BIP68
+    /// is satisfied (`UNSATISFIED_LOCKTIME`).
+    CsvTypeMismatch,
+}
+
+impl FromStr for FinalityBreak {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "height-past-tip" => Ok(Self::HeightPastTip),
+            "time-past-tip" => Ok(Self::TimePastTip),
+            "final-sequences" => Ok(Self::FinalSequences),
+            "relative-unmet" | "bip68" => Ok(Self::RelativeLockUnmet),
+            "cltv-type" | "cltv-type-mismatch" => Ok(Self::CltvTypeMismatch),
+            "csv-type" | "csv-type-mismatch" => Ok(Self::CsvTypeMismatch),
+            _ => Err(format!("Unknown finality break mode '{}'", s)),
+        }
+    }
+}
+
+/// Apply `mode` to `tx` on top of the chain tip described by `config`, touching the sequences
+/// of the inputs at `config.input_indices`, every input when empty.
+///
+/// The type mismatch modes add a lock check to the scriptSig of inputs whose scripts have
+/// none. Witness inputs are refused, as the check cannot go anywhere the spent output commits
+/// to, and P2SH inputs end up with a scriptSig that is not push only (`SIG_PUSHONLY`).
+pub fn break_finality(
+    tx: &mut Transaction,
+    mode: FinalityBreak,
+    config: &InvalidationConfig,
+) -> Result<(), Box<dyn Error>> {
+    let selected: Vec<usize> = (0..tx.input.len())
+        .filter(|index| config.input_indices.is_empty() || config.input_indices.contains(index))
+        .collect();
+
+    match mode {
+        FinalityBreak::HeightPastTip => {
+            let tip_height = config
+                .tip_height
+                .ok_or("Locking past the tip needs the tip height, see --tip-height")?;
+            let height = tip_height.saturating_add(1);
+            tx.lock_time = LockTime::from_height(height.min(LOCK_TIME_THRESHOLD - 1))
+                .expect("below the threshold");
+            set_sequences(tx, &selected, Sequence::ENABLE_LOCKTIME_NO_RBF);
+        }
+        FinalityBreak::TimePastTip => {
+            let median_time = config.tip_median_time.unwrap_or_else(Timestamp::now);
+            let time = median_time.saturating_add(1).max(LOCK_TIME_THRESHOLD);
+            tx.lock_time = LockTime::from_time(time).expect("above the threshold");
+            set_sequences(tx, &selected, Sequence::ENABLE_LOCKTIME_NO_RBF);
+        }
+        FinalityBreak::FinalSequences => {
+            let all: Vec<usize> = (0..tx.input.len()).collect();
+            set_sequences(tx, &all, Sequence::MAX);
+        }
+        FinalityBreak::RelativeLockUnmet => {
+            require_relative_locks(tx);
+            for index in selected {
+                let confirmations = config.prevout_confirmations.get(index).copied().unwrap_or(0);
+                let blocks = confirmations.saturating_add(1).min(u16::MAX as u32) as u16;
+                tx.input[index].sequence = Sequence::from_height(blocks);
+            }
+        }
+        FinalityBreak::CltvTypeMismatch => {
+            let argument = selected
+                .iter()
+                .find_map(|index| lock_argument(&tx.input[*index], OP_CLTV));
+            // The earliest lock time of each type, reached by any tip
+            match argument {
+                Some(argument) if argument < LOCK_TIME_THRESHOLD as i64 => {
+                    tx.lock_time = LockTime::from_consensus(LOCK_TIME_THRESHOLD)
+                }
+                Some(_) => tx.lock_time = LockTime::ZERO,
+                None => {
+                    for index in &selected {
+                        let argument = LOCK_TIME_THRESHOLD as i64;
+                        prepend_lock_check(&mut tx.input[*index], argument, OP_CLTV)?;
+                    }
+                    tx.lock_time = LockTime::ZERO;
+                }
+            }
+            set_sequences(tx, &selected, Sequence::ENABLE_LOCKTIME_NO_RBF);
+        }
+        FinalityBreak::CsvTypeMismatch => {
+            require_relative_locks(tx);
+            for index in selected {
+                let input = &mut tx.input[index];
+                match lock_argument(input, OP_CSV) {
+                    Some(argument) if argument & SEQUENCE_LOCKTIME_TYPE_FLAG as i64 != 0 => {
+                        input.sequence = Sequence::from_height(0)
+                    }
+                    Some(_) => input.sequence = Sequence::from_512_second_intervals(0),
+                    None => {
+                        let argument = SEQUENCE_LOCKTIME_TYPE_FLAG as i64;
+                        prepend_lock_check(input, argument, OP_CSV)?;
+                        input.sequence = Sequence::from_height(0);
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn set_sequences(tx: &mut Transaction, indices: &[usize], sequence: Sequence) {
+    for index in indices {
+        tx.input[*index].sequence = sequence;
+    }
+}
+
+/// BIP68 only applies from version 2 on.
+fn require_relative_locks(tx: &mut Transaction) {
+    if tx.version.0 < 2 {
+        tx.version = Version::TWO;
+    }
+}
+
+/// Argument pushed right before the first `opcode` of the scriptSig or witness script of
+/// `input`.
+fn lock_argument(input: &TxIn, opcode: Opcode) -> Option<i64> {
+    let witness_script = input.witness.last().map(Script::from_bytes);
+    [Some(input.script_sig.as_script()), witness_script]
+        .into_iter()
+        .flatten()
+        .find_map(|script| {
+            let instructions: Vec<Instruction> =
+                script.instructions().collect::<Result<_, _>>().ok()?;
+            instructions.windows(2).find_map(|pair| match pair[1] {
+                Instruction::Op(op) if op == opcode => pair[0].script_num(),
+                _ => None,
+            })
+        })
+}
+
+/// Prepend `<argument> opcode OP_DROP` to the scriptSig of `input`, failing for witness inputs.
+fn prepend_lock_check(
+    input: &mut TxIn,
+    argument: i64,
+    opcode: Opcode,
+) -> Result<(), Box<dyn Error>> {
+    if !input.witness.is_empty() {
+        return Err(format!(
+            "Cannot add an {} check to a witness input, its scripts carry none",
+            opcode
+        )
+        .into());
+    }
+    let check = Builder::new().push_int(argument).push_opcode(opcode).push_opcode(OP_DROP);
+    input.script_sig =
+        ScriptBuf::from_bytes([check.as_bytes(), input.script_sig.as_bytes()].concat());
+    Ok(())
+}
@@ -1,15 +1,26 @@
+pub mod amount;
 pub mod flags;
 pub mod input;
 pub mod locktime;
 pub mod output;
 pub mod script;
+pub mod serialization;
+pub mod signature;
+pub mod structure;
+#[allow(clippy::module_inception)]
 pub mod transaction;
 pub mod version;
+pub mod witness;
 
+pub use amount::*;
 pub use flags::*;
 pub use transaction::*;
 pub use input::*;
 pub use output::*;
 pub use script::*;
+pub use serialization::*;
+pub use signature::*;
+pub use structure::*;
 pub use locktime::*;
-pub use version::*;
\ No newline at end of file
+pub use version::*;
+pub use witness::*;
\ No newline at end of file
@@ -1,6 +1,6 @@
 use std::collections::HashSet;
 use bitcoin::{TxOut, Amount};
-use super::{InvalidationFlag, script::corrupt_script};
+use super::{InvalidationFlag, amount::break_amount, script::{corrupt_script, mutate_script}};
 
 pub fn invalidate_output_in_place(
     output: &mut TxOut, 
@@ -17,4 +17,14 @@ pub fn invalidate_output_in_place(
     if invalidate_all || flags.contains(&InvalidationFlag::OutputScriptPubKey) {
         output.script_pubkey = corrupt_script(&output.script_pubkey);
     }
+
+    for flag in flags {
+        match flag {
+            InvalidationFlag::OutputScript(mode) => {
+                output.script_pubkey = mutate_script(&output.script_pubkey, *mode);
+            }
+            InvalidationFlag::Amount(mode) => break_amount(output, *mode),
+            _ => {}
+        }
+    }
 }
\ No newline at end of file
@@ -1,4 +1,59 @@
+use std::str::FromStr;
+
+use bitcoin::opcodes::all::*;
+use bitcoin::opcodes::Opcode;
+use bitcoin::script::{Builder, PushBytesBuf};
 use bitcoin::ScriptBuf;
+use rand::seq::IndexedRandom;
+
+/// Largest element that can be pushed on the stack.
+pub const MAX_SCRIPT_ELEMENT_SIZE: usize = 520;
+/// Most non-push opcodes a script may contain.
+pub const MAX_OPS_PER_SCRIPT: usize = 201;
+/// Largest script that can be executed.
+pub const MAX_SCRIPT_SIZE: usize = 10_000;
+
+/// Opcodes disabled since 2010, failing the script even in an unexecuted branch.
+pub const DISABLED_OPCODES: [Opcode; 15] = [
+    OP_CAT, OP_SUBSTR, OP_LEFT, OP_RIGHT, OP_INVERT, OP_AND, OP_OR, OP_XOR, OP_2MUL, OP_2DIV,
+    OP_MUL, OP_DIV, OP_MOD, OP_LSHIFT, OP_RSHIFT,
+];
+
+/// Opcode aware script mutations, each with the script error it is expected to raise.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ScriptBreak {
+    /// Prepend a random disabled opcode such as `OP_CAT` or `OP_MUL` (`DISABLED_OPCODE`).
+    DisabledOpcode,
+    /// Prepend `OP_RETURN` (`OP_RETURN`).
+    OpReturn,
+    /// Prepend a one byte push through `OP_PUSHDATA1` (`MINIMALDATA`, policy).
+    NonMinimalPush,
+    /// Prepend a push one byte over `MAX_SCRIPT_ELEMENT_SIZE` (`PUSH_SIZE`).
+    OversizedPush,
+    /// Append an `OP_ENDIF` without its `OP_IF` (`UNBALANCED_CONDITIONAL`).
+    UnbalancedConditional,
+    /// Append `OP_NOP`s past `MAX_OPS_PER_SCRIPT` (`OP_COUNT`).
+    TooManyOps,
+    /// Append pushes until the script is larger than `MAX_SCRIPT_SIZE` (`SCRIPT_SIZE`).
+    OversizedScript,
+}
+
+impl FromStr for ScriptBreak {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "disabled-opcode" | "disabled" => Ok(Self::DisabledOpcode),
+            "op-return" => Ok(Self::OpReturn),
+            "non-minimal-push" => Ok(Self::NonMinimalPush),
+            "oversized-push" | "push-size" => Ok(Self::OversizedPush),
+            "unbalanced-if" | "unbalanced-conditional" => Ok(Self::UnbalancedConditional),
+            "too-many-ops" | "op-count" => Ok(Self::TooManyOps),
+            "oversized-script" | "script-size" => Ok(Self::OversizedScript),
+            _ => Err(format!("Unknown script break mode '{}'", s)),
+        }
+    }
+}
 
 pub fn corrupt_script(script: &ScriptBuf) -> ScriptBuf {
     let mut bytes = script.as_bytes().to_vec();
@@ -8,4 +63,36 @@ pub fn corrupt_script(script: &ScriptBuf) -> ScriptBuf {
         bytes.push(0x51); // Add OP_1 to empty script
     }
     ScriptBuf::from_bytes(bytes)
-}
\ No newline at end of file
+}
+
+/// Apply `mode` to `script`, keeping its original opcodes around the mutation.
+pub fn mutate_script(script: &ScriptBuf, mode: ScriptBreak) -> ScriptBuf {
+    let original = script.as_bytes();
+
+    let (prefix, suffix): (Vec<u8>, Vec<u8>) = match mode {
+        ScriptBreak::DisabledOpcode => {
+            let opcode = DISABLED_OPCODES.choose(&mut rand::rng()).expect("not empty");
+            (vec![opcode.to_u8()], vec![])
+        }
+        ScriptBreak::OpReturn => (vec![OP_RETURN.to_u8()], vec![]),
+        ScriptBreak::NonMinimalPush => (vec![OP_PUSHDATA1.to_u8(), 0x01, 0x01], vec![]),
+        ScriptBreak::OversizedPush => {
+            let data = PushBytesBuf::try_from(vec![0u8; MAX_SCRIPT_ELEMENT_SIZE + 1])
+                .expect("below the push limit");
+            (Builder::new().push_slice(data).into_bytes(), vec![])
+        }
+        ScriptBreak::UnbalancedConditional => (vec![], vec![OP_ENDIF.to_u8()]),
+        ScriptBreak::TooManyOps => (vec![], vec![OP_NOP.to_u8(); MAX_OPS_PER_SCRIPT + 1]),
+        ScriptBreak::OversizedScript => {
+            let chunk = PushBytesBuf::try_from(vec![0u8; MAX_SCRIPT_ELEMENT_SIZE])
+                .expect("below the push limit");
+            let mut builder = Builder::new();
+            while original.len() + builder.len() <= MAX_SCRIPT_SIZE {
+                builder = builder.push_slice(&chunk);
+            }
+            (vec![], builder.into_bytes())
+        }
+    };
+
+    ScriptBuf::from_bytes([prefix.as_slice(), original, suffix.as_slice()].concat())
+}
@@ -0,0 +1,102 @@
+use std::str::FromStr;
+
+use bitcoin::consensus::encode::{Encodable, VarInt};
+use bitcoin::{Transaction, Witness};
+
+/// Segwit marker byte, read by legacy decoders as an empty input list.
+pub const SEGWIT_MARKER: u8 = 0x00;
+/// The only segwit flag byte defined so far.
+pub const SEGWIT_FLAG: u8 = 0x01;
+/// Bytes appended after the locktime by `SerializationBreak::TrailingBytes`.
+pub const TRAILING_BYTES: [u8; 4] = [0xde, 0xad, 0xbe, 0xef];
+
+/// Byte level encoding corruptions that no `Transaction` can hold, each with the decoding
+/// error it is expected to raise.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SerializationBreak {
+    /// Keep the marker and flag while every witness is empty (`Superfluous witness record`).
+    EmptyWitnesses,
+    /// Use a flag byte other than 0x01 (`Unknown transaction optional data`).
+    WrongFlag,
+    /// Leave out the witness of the last input, so the locktime is read as a witness.
+    WitnessCountMismatch,
+    /// Append bytes after the locktime (`TX decode failed`, data left in the stream).
+    TrailingBytes,
+    /// Encode the input count as a three bytes CompactSize (`non-canonical ReadCompactSize()`).
+    NonCanonicalVarint,
+}
+
+impl FromStr for SerializationBreak {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "empty-witnesses" | "superfluous-witness" => Ok(Self::EmptyWitnesses),
+            "wrong-flag" | "flag" => Ok(Self::WrongFlag),
+            "witness-count" | "witness-count-mismatch" => Ok(Self::WitnessCountMismatch),
+            "trailing-bytes" | "trailing" => Ok(Self::TrailingBytes),
+            "non-canonical-varint" | "varint" => Ok(Self::NonCanonicalVarint),
+            _ => Err(format!("Unknown serialization break mode '{}'", s)),
+        }
+    }
+}
+
+/// Serialize `tx` the way `consensus::serialize` does, with every mode of `modes` applied to
+/// the bytes.
+///
+/// Unlike `consensus::serialize`, a transaction without inputs gets the legacy encoding, so
+/// nodes decode it as such and reject the empty input list rather than the witness record.
+pub fn serialize_broken(tx: &Transaction, modes: &[SerializationBreak]) -> Vec<u8> {
+    let has = |mode| modes.contains(&mode);
+    let mut bytes = Vec::new();
+
+    let use_segwit = tx.input.iter().any(|input| !input.witness.is_empty())
+        || has(SerializationBreak::EmptyWitnesses)
+        || has(SerializationBreak::WrongFlag)
+        || has(SerializationBreak::WitnessCountMismatch);
+
+    tx.version.consensus_encode(&mut bytes).expect("in-memory writers don't error");
+    if use_segwit {
+        bytes.push(SEGWIT_MARKER);
+        bytes.push(if has(SerializationBreak::WrongFlag) { SEGWIT_FLAG + 1 } else { SEGWIT_FLAG });
+    }
+
+    if has(SerializationBreak::NonCanonicalVarint) {
+        bytes.push(0xfd);
+        bytes.extend_from_slice(&(tx.input.len() as u16).to_le_bytes());
+    } else {
+        VarInt(tx.input.len() as u64)
+            .consensus_encode(&mut bytes)
+            .expect("in-memory writers don't error");
+    }
+    for input in &tx.input {
+        input.consensus_encode(&mut bytes).expect("in-memory writers don't error");
+    }
+    tx.output.consensus_encode(&mut bytes).expect("in-memory writers don't error");
+
+    if use_segwit {
+        let mut witnesses: Vec<Witness> = tx
+            .input
+            .iter()
+            .map(|input| {
+                if has(SerializationBreak::EmptyWitnesses) {
+                    Witness::new()
+                } else {
+                    input.witness.clone()
+                }
+            })
+            .collect();
+        if has(SerializationBreak::WitnessCountMismatch) {
+            witnesses.pop();
+        }
+        for witness in &witnesses {
+            witness.consensus_encode(&mut bytes).expect("in-memory writers don't error");
+        }
+    }
+
+    tx.lock_time.consensus_encode(&mut bytes).expect("in-memory writers don't error");
+    if has(SerializationBreak::TrailingBytes) {
+        bytes.extend_from_slice(&TRAILING_BYTES);
+    }
+    bytes
+}
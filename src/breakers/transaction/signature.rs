@@ -0,0 +1,330 @@
+use std::str::FromStr;
+
+use bitcoin::{
+    key::Keypair,
+    script::{Builder, Instruction, PushBytesBuf},
+    secp256k1::{ecdsa, Message, Secp256k1, SecretKey},
+    taproot::{
+        TAPROOT_ANNEX_PREFIX, TAPROOT_CONTROL_BASE_SIZE, TAPROOT_CONTROL_MAX_SIZE,
+        TAPROOT_CONTROL_NODE_SIZE, TAPROOT_LEAF_MASK, TAPROOT_LEAF_TAPSCRIPT,
+    },
+    ScriptBuf, TxIn, Witness,
+};
+use rand::Rng;
+
+/// A sighash type byte defined for neither ECDSA nor Schnorr signatures.
+pub const UNDEFINED_SIGHASH_TYPE: u8 = 0x04;
+
+/// Signature level corruptions, each with the script error it is expected to raise.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SignatureBreak {
+    /// Replace S of an ECDSA signature with `n - S` (`SIG_HIGH_S`, policy).
+    HighS,
+    /// Pad R of an ECDSA signature with a superfluous zero byte (`SIG_DER`, BIP66).
+    DerPadding,
+    /// Encode R of an ECDSA signature as a negative integer (`SIG_DER`, BIP66).
+    NegativeR,
+    /// Declare one byte more than the DER sequence holds (`SIG_DER`, BIP66).
+    WrongDerLength,
+    /// Use a sighash type byte that is not defined (`SIG_HASHTYPE` or `SCHNORR_SIG_HASHTYPE`).
+    WrongSighashType,
+    /// Set R of a Schnorr signature above the field size (`SCHNORR_SIG`).
+    SchnorrInvalidR,
+    /// Spell out the default sighash type as a 65th byte (`SCHNORR_SIG_HASHTYPE`).
+    SchnorrDefaultSighash,
+    /// A well formed signature over a random message with a random key, which fails
+    /// verification (`NULLFAIL` for ECDSA, `SCHNORR_SIG` for Schnorr).
+    WrongMessage,
+}
+
+impl FromStr for SignatureBreak {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "high-s" => Ok(Self::HighS),
+            "der-padding" => Ok(Self::DerPadding),
+            "negative-r" => Ok(Self::NegativeR),
+            "der-length" | "wrong-der-length" => Ok(Self::WrongDerLength),
+            "sighash" | "wrong-sighash" => Ok(Self::WrongSighashType),
+            "schnorr-r" | "invalid-r" => Ok(Self::SchnorrInvalidR),
+            "schnorr-default-sighash" => Ok(Self::SchnorrDefaultSighash),
+            "wrong-message" => Ok(Self::WrongMessage),
+            _ => Err(format!("Unknown signature break mode '{}'", s)),
+        }
+    }
+}
+
+impl SignatureBreak {
+    fn applies_to_ecdsa(&self) -> bool {
+        !matches!(self, Self::SchnorrInvalidR | Self::SchnorrDefaultSighash)
+    }
+
+    fn applies_to_schnorr(&self) -> bool {
+        matches!(
+            self,
+            Self::WrongSighashType
+                | Self::SchnorrInvalidR
+                | Self::SchnorrDefaultSighash
+                | Self::WrongMessage
+        )
+    }
+}
+
+/// Processor for ECDSA and Schnorr signature modifications
+pub struct SignatureProcessor;
+
+impl SignatureProcessor {
+    /// Break the first signature of `input` `mode` applies to, looking at the witness before
+    /// the scriptSig. Schnorr signatures are only looked for where a taproot spend carries
+    /// them, see `schnorr_index`. Returns whether a signature was found.
+    pub fn break_input(input: &mut TxIn, mode: SignatureBreak) -> bool {
+        let mut items = input.witness.to_vec();
+        let ecdsa = items.iter().position(|item| mode.applies_to_ecdsa() && Self::is_ecdsa(item));
+        let found = match ecdsa {
+            Some(index) => Self::break_ecdsa(&items[index], mode).map(|broken| (index, broken)),
+            None if mode.applies_to_schnorr() => Self::schnorr_index(&items).and_then(|index| {
+                Self::break_schnorr(&items[index], mode).map(|broken| (index, broken))
+            }),
+            None => None,
+        };
+        if let Some((index, broken)) = found {
+            items[index] = broken;
+            input.witness = Witness::from_slice(&items);
+            return true;
+        }
+
+        if !mode.applies_to_ecdsa() {
+            return false;
+        }
+        match Self::break_script_sig(&input.script_sig, mode) {
+            Some(script_sig) => {
+                input.script_sig = script_sig;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Break `item` when it holds a signature `mode` applies to, Schnorr signatures being
+    /// recognized by their size alone.
+    pub fn break_item(item: &[u8], mode: SignatureBreak) -> Option<Vec<u8>> {
+        if mode.applies_to_ecdsa() && Self::is_ecdsa(item) {
+            Self::break_ecdsa(item, mode)
+        } else if mode.applies_to_schnorr() && Self::is_schnorr(item) {
+            Self::break_schnorr(item, mode)
+        } else {
+            None
+        }
+    }
+
+    /// Whether `item` is a strict DER signature followed by a sighash type byte.
+    pub fn is_ecdsa(item: &[u8]) -> bool {
+        Self::is_strict_der(item) && ecdsa::Signature::from_der(&item[..item.len() - 1]).is_ok()
+    }
+
+    /// BIP66 encoding check of a DER signature followed by a sighash type byte, as done by
+    /// `IsValidSignatureEncoding`.
+    pub fn is_strict_der(sig: &[u8]) -> bool {
+        if sig.len() < 9 || sig.len() > 73 || sig[0] != 0x30 || sig[1] as usize != sig.len() - 3 {
+            return false;
+        }
+
+        let len_r = sig[3] as usize;
+        if 5 + len_r >= sig.len() {
+            return false;
+        }
+        let len_s = sig[5 + len_r] as usize;
+        if len_r + len_s + 7 != sig.len() {
+            return false;
+        }
+
+        let is_valid_integer = |marker: u8, len: usize, start: usize| {
+            marker == 0x02
+                && len != 0
+                && sig[start] & 0x80 == 0
+                && !(len > 1 && sig[start] == 0x00 && sig[start + 1] & 0x80 == 0)
+        };
+        is_valid_integer(sig[2], len_r, 4) && is_valid_integer(sig[len_r + 4], len_s, len_r + 6)
+    }
+
+    /// Whether `item` has the size of a Schnorr signature, with or without sighash type byte.
+    pub fn is_schnorr(item: &[u8]) -> bool {
+        item.len() == 64 || item.len() == 65
+    }
+
+    /// Position of the Schnorr signature in a taproot witness: the only item of a key path
+    /// spend, or the first item of a script path spend, below its script and control block.
+    /// An annex is skipped.
+    pub fn schnorr_index(items: &[Vec<u8>]) -> Option<usize> {
+        let stack = match items.split_last() {
+            Some((annex, stack))
+                if !stack.is_empty() && annex.first() == Some(&TAPROOT_ANNEX_PREFIX) =>
+            {
+                stack
+            }
+            _ => items,
+        };
+
+        let is_taproot = stack.len() == 1
+            || (stack.len() >= 3 && Self::is_control_block(stack.last()?));
+        (is_taproot && Self::is_schnorr(&stack[0])).then_some(0)
+    }
+
+    /// Whether `item` has the size and leaf version of a tapscript control block.
+    fn is_control_block(item: &[u8]) -> bool {
+        item.len() >= TAPROOT_CONTROL_BASE_SIZE
+            && item.len() <= TAPROOT_CONTROL_MAX_SIZE
+            && (item.len() - TAPROOT_CONTROL_BASE_SIZE).is_multiple_of(TAPROOT_CONTROL_NODE_SIZE)
+            && item[0] & TAPROOT_LEAF_MASK == TAPROOT_LEAF_TAPSCRIPT
+    }
+
+    /// Break a DER signature followed by its sighash type byte.
+    pub fn break_ecdsa(sig: &[u8], mode: SignatureBreak) -> Option<Vec<u8>> {
+        let (der, sighash_type) = sig.split_at(sig.len().checked_sub(1)?);
+        let compact = ecdsa::Signature::from_der(der).ok()?.serialize_compact();
+        let (r, s) = compact.split_at(32);
+
+        let mut broken = match mode {
+            SignatureBreak::HighS => Self::encode_der(&Self::der_integer(r), &Self::high_s(s)?),
+            SignatureBreak::DerPadding => {
+                let mut padded = vec![0x00];
+                padded.extend(Self::der_integer(r));
+                Self::encode_der(&padded, &Self::der_integer(s))
+            }
+            SignatureBreak::NegativeR => {
+                let mut negative = Self::trim_zeros(r).to_vec();
+                negative[0] |= 0x80;
+                Self::encode_der(&negative, &Self::der_integer(s))
+            }
+            SignatureBreak::WrongDerLength => {
+                let mut der = der.to_vec();
+                der[1] = der[1].wrapping_add(1);
+                der
+            }
+            SignatureBreak::WrongSighashType => der.to_vec(),
+            SignatureBreak::WrongMessage => {
+                let secp = Secp256k1::new();
+                secp.sign_ecdsa(&Self::random_message(), &Self::random_key())
+                    .serialize_der()
+                    .to_vec()
+            }
+            SignatureBreak::SchnorrInvalidR | SignatureBreak::SchnorrDefaultSighash => {
+                return None
+            }
+        };
+
+        broken.push(match mode {
+            SignatureBreak::WrongSighashType => UNDEFINED_SIGHASH_TYPE,
+            _ => sighash_type[0],
+        });
+        Some(broken)
+    }
+
+    /// Break a 64-byte Schnorr signature, or a 65-byte one carrying its sighash type.
+    pub fn break_schnorr(sig: &[u8], mode: SignatureBreak) -> Option<Vec<u8>> {
+        if !Self::is_schnorr(sig) {
+            return None;
+        }
+        let sighash_type = sig.get(64).copied();
+
+        let broken = match mode {
+            SignatureBreak::SchnorrInvalidR => {
+                let mut broken = sig.to_vec();
+                broken[..32].copy_from_slice(&[0xff; 32]);
+                broken
+            }
+            SignatureBreak::SchnorrDefaultSighash => {
+                let mut broken = sig[..64].to_vec();
+                broken.push(0x00);
+                broken
+            }
+            SignatureBreak::WrongSighashType => {
+                let mut broken = sig[..64].to_vec();
+                broken.push(UNDEFINED_SIGHASH_TYPE);
+                broken
+            }
+            SignatureBreak::WrongMessage => {
+                let secp = Secp256k1::new();
+                let keypair = Keypair::from_secret_key(&secp, &Self::random_key());
+                let mut broken =
+                    secp.sign_schnorr(&Self::random_message(), &keypair).as_ref().to_vec();
+                broken.extend(sighash_type);
+                broken
+            }
+            _ => return None,
+        };
+        Some(broken)
+    }
+
+    /// Rebuild `script_sig` with its first ECDSA signature push broken.
+    fn break_script_sig(script_sig: &ScriptBuf, mode: SignatureBreak) -> Option<ScriptBuf> {
+        let mut builder = Builder::new();
+        let mut broken = false;
+
+        for instruction in script_sig.instructions() {
+            match instruction.ok()? {
+                Instruction::PushBytes(push) if !broken && Self::is_ecdsa(push.as_bytes()) => {
+                    let bytes = Self::break_ecdsa(push.as_bytes(), mode)?;
+                    builder = builder.push_slice(PushBytesBuf::try_from(bytes).ok()?);
+                    broken = true;
+                }
+                Instruction::PushBytes(push) => builder = builder.push_slice(push),
+                Instruction::Op(op) => builder = builder.push_opcode(op),
+            }
+        }
+
+        broken.then(|| builder.into_script())
+    }
+
+    /// `n - s` when `s` is in the lower half of the curve order, `s` otherwise.
+    fn high_s(s: &[u8]) -> Option<Vec<u8>> {
+        let mut low = ecdsa::Signature::from_compact(&[[1u8; 32].as_slice(), s].concat()).ok()?;
+        low.normalize_s();
+        let is_low = &low.serialize_compact()[32..] == s;
+
+        let high = if is_low {
+            SecretKey::from_slice(s).ok()?.negate().secret_bytes().to_vec()
+        } else {
+            s.to_vec()
+        };
+        Some(Self::der_integer(&high))
+    }
+
+    /// Minimal DER integer encoding of the big-endian unsigned `value`.
+    fn der_integer(value: &[u8]) -> Vec<u8> {
+        let trimmed = Self::trim_zeros(value);
+        let mut integer = Vec::with_capacity(trimmed.len() + 1);
+        if trimmed[0] & 0x80 != 0 {
+            integer.push(0x00);
+        }
+        integer.extend_from_slice(trimmed);
+        integer
+    }
+
+    fn trim_zeros(value: &[u8]) -> &[u8] {
+        let start = value.iter().position(|byte| *byte != 0).unwrap_or(value.len() - 1);
+        &value[start..]
+    }
+
+    /// DER sequence of two integers, taken as already encoded.
+    fn encode_der(r: &[u8], s: &[u8]) -> Vec<u8> {
+        let mut der = vec![0x30, (4 + r.len() + s.len()) as u8, 0x02, r.len() as u8];
+        der.extend_from_slice(r);
+        der.extend_from_slice(&[0x02, s.len() as u8]);
+        der.extend_from_slice(s);
+        der
+    }
+
+    fn random_message() -> Message {
+        Message::from_digest(rand::rng().random())
+    }
+
+    fn random_key() -> SecretKey {
+        loop {
+            if let Ok(key) = SecretKey::from_slice(&rand::rng().random::<[u8; 32]>()) {
+                return key;
+            }
+        }
+    }
+}
@@ -0,0 +1,93 @@
+use std::error::Error;
+use std::str::FromStr;
+
+use bitcoin::opcodes::all::OP_RETURN;
+use bitcoin::{Amount, OutPoint, ScriptBuf, Transaction, TxIn, TxOut};
+
+/// Smallest non-witness size a standard transaction may have, below it a transaction of
+/// 64 bytes could pass for an inner merkle node.
+pub const MIN_STANDARD_TX_NONWITNESS_SIZE: usize = 65;
+
+/// Changes to the shape of a transaction, each with the rejection it is expected to raise.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StructureBreak {
+    /// Remove every input (`bad-txns-vin-empty`).
+    NoInputs,
+    /// Remove every output (`bad-txns-vout-empty`).
+    NoOutputs,
+    /// Spend the same outpoint twice (`bad-txns-inputs-duplicate`).
+    DuplicateInput,
+    /// Give a non-coinbase input the null prevout (`bad-txns-prevout-null`).
+    NullPrevout,
+    /// Shrink the transaction to 64 non-witness bytes (`tx-size-small`, policy).
+    Undersized,
+}
+
+impl FromStr for StructureBreak {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "no-inputs" | "vin-empty" => Ok(Self::NoInputs),
+            "no-outputs" | "vout-empty" => Ok(Self::NoOutputs),
+            "duplicate-input" | "duplicate" => Ok(Self::DuplicateInput),
+            "null-prevout" => Ok(Self::NullPrevout),
+            "undersized" | "too-small" => Ok(Self::Undersized),
+            _ => Err(format!("Unknown structure break mode '{}'", s)),
+        }
+    }
+}
+
+/// Apply `mode` to `tx`, using the first of `input_indices`, the first input when empty, for
+/// the modes acting on a single input. Fails when `DuplicateInput` or `NullPrevout` has no such
+/// input.
+pub fn break_structure(
+    tx: &mut Transaction,
+    mode: StructureBreak,
+    input_indices: &[usize],
+) -> Result<(), Box<dyn Error>> {
+    let index = input_indices.first().copied().unwrap_or(0);
+
+    match mode {
+        StructureBreak::NoInputs => tx.input.clear(),
+        StructureBreak::NoOutputs => tx.output.clear(),
+        StructureBreak::DuplicateInput => {
+            let input = tx.input.get(index).cloned().ok_or_else(|| {
+                format!("No input {} to duplicate, the transaction has {}", index, tx.input.len())
+            })?;
+            tx.input.push(input);
+        }
+        StructureBreak::NullPrevout => {
+            if index >= tx.input.len() {
+                return Err(format!(
+                    "No input {} to null, the transaction has {}",
+                    index,
+                    tx.input.len()
+                )
+                .into());
+            }
+            // A single input with the null prevout is a coinbase, so keep at least two
+            while tx.input.len() < 2 {
+                tx.input.push(TxIn::default());
+            }
+            tx.input[index].previous_output = OutPoint::null();
+        }
+        StructureBreak::Undersized => undersize(tx, index),
+    }
+
+    Ok(())
+}
+
+/// Keep a single input with an empty scriptSig and a single output with a four bytes script,
+/// 64 bytes once the witness is stripped.
+fn undersize(tx: &mut Transaction, index: usize) {
+    let mut input = tx.input.get(index).cloned().unwrap_or_default();
+    input.script_sig = ScriptBuf::new();
+    let value = tx.output.first().map(|output| output.value).unwrap_or(Amount::ZERO);
+
+    tx.input = vec![input];
+    tx.output = vec![TxOut {
+        value,
+        script_pubkey: ScriptBuf::from_bytes(vec![OP_RETURN.to_u8(), 0x02, 0x00, 0x00]),
+    }];
+}
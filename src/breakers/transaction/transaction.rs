@@ -1,6 +1,7 @@
 use std::collections::HashSet;
-use bitcoin::{Transaction, consensus::deserialize};
-use super::{InvalidationFlag, input::*, output::*, version::*, locktime::*};
+use std::error::Error;
+use bitcoin::{Amount, OutPoint, Transaction, TxIn, Txid, consensus::deserialize, hashes::Hash};
+use super::{InvalidationFlag, amount::*, input::*, output::*, structure::*, version::*, locktime::*};
 
 #[derive(Default)]
 pub struct TransactionInvalidator;
@@ -9,13 +10,87 @@ pub struct TransactionInvalidator;
 pub struct BitcoinTransactionDecoder;
 
 
+/// Flags to apply along with the inputs and outputs they are applied to.
+#[derive(Debug, Clone, Default)]
+pub struct InvalidationConfig {
+    pub flags: HashSet<InvalidationFlag>,
+    /// Inputs touched by the input level flags, every input when empty.
+    pub input_indices: Vec<usize>,
+    /// Outputs touched by the output level flags, every output when empty.
+    pub output_indices: Vec<usize>,
+    /// Values of the outputs spent by the inputs, used by `AmountBreak::ExceedInputs`.
+    pub prevout_values: Vec<Amount>,
+    /// Height of the chain tip the finality breaks are checked against.
+    pub tip_height: Option<u32>,
+    /// Median time past of the chain tip, the system time when not given.
+    pub tip_median_time: Option<u32>,
+    /// Confirmations of the outputs spent by the inputs, 0 for the missing ones.
+    pub prevout_confirmations: Vec<u32>,
+    /// Output of a second unconfirmed transaction spent by `VersionBreak::TrucTopology`.
+    pub truc_parent: Option<OutPoint>,
+}
+
 impl TransactionInvalidator {
-    pub fn invalidate(mut tx: Transaction, flags: &HashSet<InvalidationFlag>) -> Transaction {
+    pub fn invalidate(
+        tx: Transaction,
+        flags: &HashSet<InvalidationFlag>,
+    ) -> Result<Transaction, Box<dyn Error>> {
+        Self::invalidate_with_config(
+            tx,
+            &InvalidationConfig {
+                flags: flags.clone(),
+                ..Default::default()
+            },
+        )
+    }
+
+    /// Apply the flags of `config` to the selected inputs and outputs only, leaving the
+    /// others untouched. Fails when a selected input or output does not exist, or when a flag
+    /// cannot be applied to the transaction at all.
+    pub fn invalidate_with_config(
+        mut tx: Transaction,
+        config: &InvalidationConfig,
+    ) -> Result<Transaction, Box<dyn Error>> {
+        let flags = &config.flags;
         let should_invalidate_all = flags.contains(&InvalidationFlag::All);
+
+        if let Some(index) = config.input_indices.iter().find(|index| **index >= tx.input.len()) {
+            return Err(
+                format!("No input {}, the transaction has {}", index, tx.input.len()).into()
+            );
+        }
+        if let Some(index) = config.output_indices.iter().find(|index| **index >= tx.output.len()) {
+            return Err(
+                format!("No output {}, the transaction has {}", index, tx.output.len()).into()
+            );
+        }
+
+        // Witness breaks only need to apply to one of the selected inputs
+        for flag in flags {
+            if let InvalidationFlag::Witness(mode) = flag {
+                let applies = tx.input.iter().enumerate().any(|(index, input)| {
+                    Self::is_selected(&config.input_indices, index)
+                        && mode.applies_to(&input.witness)
+                });
+                if !applies {
+                    return Err(
+                        format!("Witness break {:?} applies to none of the inputs", mode).into()
+                    );
+                }
+            }
+        }
         
         // Invalidate transaction structure (affects txid) - do this first
         if should_invalidate_all || flags.contains(&InvalidationFlag::InputTxid) {
-            Self::corrupt_txid(&mut tx);
+            if config.input_indices.is_empty() {
+                Self::corrupt_txid(&mut tx);
+            } else {
+                for index in &config.input_indices {
+                    if let Some(input) = tx.input.get_mut(*index) {
+                        Self::corrupt_prevout_txid(input);
+                    }
+                }
+            }
         }
         
         // Invalidate version
@@ -29,16 +104,58 @@ impl TransactionInvalidator {
         }
         
         // Invalidate inputs
-        for input in tx.input.iter_mut() {
-            invalidate_input_in_place(input, flags, should_invalidate_all);
+        for (index, input) in tx.input.iter_mut().enumerate() {
+            if Self::is_selected(&config.input_indices, index) {
+                invalidate_input_in_place(input, flags, should_invalidate_all);
+            }
         }
         
         // Invalidate outputs
-        for output in tx.output.iter_mut() {
-            invalidate_output_in_place(output, flags, should_invalidate_all);
+        for (index, output) in tx.output.iter_mut().enumerate() {
+            if Self::is_selected(&config.output_indices, index) {
+                invalidate_output_in_place(output, flags, should_invalidate_all);
+            }
+        }
+
+        // Finality breaks, after the input level sequence flags
+        for flag in flags {
+            if let InvalidationFlag::Finality(mode) = flag {
+                break_finality(&mut tx, *mode, config)?;
+            }
+        }
+
+        // Version breaks after the finality ones, so an explicit version wins
+        for flag in flags {
+            if let InvalidationFlag::TxVersion(mode) = flag {
+                break_version(&mut tx, *mode, config)?;
+            }
+        }
+
+        // Amount breaks looking at the outputs together, once each output is final
+        for flag in flags {
+            match flag {
+                InvalidationFlag::Amount(AmountBreak::TotalOverflow) => {
+                    overflow_total(&mut tx, &config.output_indices)
+                }
+                InvalidationFlag::Amount(AmountBreak::ExceedInputs) => {
+                    exceed_inputs(&mut tx, &config.prevout_values, &config.output_indices)?
+                }
+                _ => {}
+            }
+        }
+
+        // Structure breaks last, as they add and remove inputs and outputs
+        for flag in flags {
+            if let InvalidationFlag::Structure(mode) = flag {
+                break_structure(&mut tx, *mode, &config.input_indices)?;
+            }
         }
         
-        tx
+        Ok(tx)
+    }
+
+    fn is_selected(indices: &[usize], index: usize) -> bool {
+        indices.is_empty() || indices.contains(&index)
     }
 
     // Helper methods
@@ -54,6 +171,13 @@ impl TransactionInvalidator {
         // Compute and return the new transaction ID
         tx.compute_txid()
     }
+
+    // Point a single input to a transaction that does not exist
+    fn corrupt_prevout_txid(input: &mut TxIn) {
+        let mut bytes = input.previous_output.txid.to_byte_array();
+        bytes[0] ^= 0x01;
+        input.previous_output.txid = Txid::from_byte_array(bytes);
+    }
 }
 
 
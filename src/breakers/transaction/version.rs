@@ -1,5 +1,102 @@
+use std::error::Error;
+use std::str::FromStr;
+
 use bitcoin::blockdata::transaction::Version;
+use bitcoin::{OutPoint, Sequence, Transaction};
+
+use super::transaction::InvalidationConfig;
+
+/// Highest version relayed by nodes, version 3 being TRUC (BIP431).
+pub const TX_MAX_STANDARD_VERSION: i32 = 3;
+
+/// Version corruptions, each documented with the rule it targets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum VersionBreak {
+    /// Version 0, below the standard range (`version`, policy).
+    Zero,
+    /// Version 4, above `TX_MAX_STANDARD_VERSION` (`version`, policy).
+    AboveStandard,
+    /// Version -1, non-standard, and still read as at least 2 by BIP68 which casts it to
+    /// unsigned.
+    Negative,
+    /// Version 1 with relative lock sequences, which BIP68 ignores so any
+    /// `OP_CHECKSEQUENCEVERIFY` fails (`UNSATISFIED_LOCKTIME`).
+    RelativeLocksInVersionOne,
+    /// Version 3 also spending `truc_parent`, an output of a second unconfirmed transaction,
+    /// where a TRUC child may only have one unconfirmed parent (`TRUC-violation`, policy).
+    TrucTopology,
+    /// Any exact version.
+    Exact(i32),
+}
+
+impl FromStr for VersionBreak {
+    type Err = String;
 
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "zero" => Ok(Self::Zero),
+            "above-standard" | "four" => Ok(Self::AboveStandard),
+            "negative" => Ok(Self::Negative),
+            "bip68-v1" | "relative-v1" => Ok(Self::RelativeLocksInVersionOne),
+            "truc" | "truc-topology" => Ok(Self::TrucTopology),
+            other => other
+                .parse()
+                .map(Self::Exact)
+                .map_err(|_| format!("Unknown version break mode '{}'", s)),
+        }
+    }
+}
+
+/// Move the version out of the standard range, without overflowing.
 pub fn invalidate_version(v: Version) -> Version {
-    Version(v.0 + 15)
-}
\ No newline at end of file
+    if v.0 == TX_MAX_STANDARD_VERSION + 1 {
+        Version(0)
+    } else {
+        Version(TX_MAX_STANDARD_VERSION + 1)
+    }
+}
+
+/// Apply `mode` to `tx`, the sequences set by `RelativeLocksInVersionOne` going to the inputs
+/// at `config.input_indices`, every input when empty. Fails when `TrucTopology` has no
+/// `config.truc_parent` to spend, or already spends it.
+pub fn break_version(
+    tx: &mut Transaction,
+    mode: VersionBreak,
+    config: &InvalidationConfig,
+) -> Result<(), Box<dyn Error>> {
+    tx.version = match mode {
+        VersionBreak::Zero => Version(0),
+        VersionBreak::AboveStandard => Version(TX_MAX_STANDARD_VERSION + 1),
+        VersionBreak::Negative => Version(-1),
+        VersionBreak::RelativeLocksInVersionOne => {
+            for (index, input) in tx.input.iter_mut().enumerate() {
+                if config.input_indices.is_empty() || config.input_indices.contains(&index) {
+                    input.sequence = Sequence::from_height(1);
+                }
+            }
+            Version::ONE
+        }
+        VersionBreak::TrucTopology => {
+            let parent = config.truc_parent.ok_or(
+                "A second TRUC parent needs an unconfirmed outpoint to spend, see --truc-parent",
+            )?;
+            add_parent(tx, parent)?;
+            Version(3)
+        }
+        VersionBreak::Exact(version) => Version(version),
+    };
+    Ok(())
+}
+
+/// Spend `parent` as well, with the sequence and scripts of the first input. Fails when the
+/// transaction already spends it, as it would still have a single unconfirmed parent.
+fn add_parent(tx: &mut Transaction, parent: OutPoint) -> Result<(), Box<dyn Error>> {
+    if tx.input.iter().any(|input| input.previous_output == parent) {
+        return Err(format!("The transaction already spends the TRUC parent {}", parent).into());
+    }
+
+    let mut input = tx.input.first().cloned().unwrap_or_default();
+    input.previous_output = parent;
+    tx.input.push(input);
+    Ok(())
+}
@@ -0,0 +1,100 @@
+use std::str::FromStr;
+
+use bitcoin::opcodes::all::OP_NOP;
+use bitcoin::{TxIn, Witness};
+
+/// Most witness elements a standard P2WSH spend may have, the script excluded.
+pub const MAX_STANDARD_P2WSH_STACK_ITEMS: usize = 100;
+/// Largest witness element a standard P2WSH spend may have, the script excluded.
+pub const MAX_STANDARD_P2WSH_STACK_ITEM_SIZE: usize = 80;
+/// First byte marking the last witness element as a taproot annex.
+pub const ANNEX_TAG: u8 = 0x50;
+
+/// Witness stack manipulations, each with the rejection it is expected to raise.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WitnessBreak {
+    /// Add an element at the bottom of the stack (`WITNESS_PROGRAM_MISMATCH` for P2WPKH,
+    /// `CLEANSTACK` for P2WSH).
+    ExtraElement,
+    /// Drop the last element, the witness script or public key.
+    DropLastElement,
+    /// Swap the first two elements.
+    SwapElements,
+    /// Append `OP_NOP` to the last element so it no longer hashes to the program
+    /// (`WITNESS_PROGRAM_MISMATCH`).
+    ScriptMismatch,
+    /// Push elements past `MAX_STANDARD_P2WSH_STACK_ITEMS` (policy).
+    TooManyElements,
+    /// Push an element over `MAX_STANDARD_P2WSH_STACK_ITEM_SIZE` bytes (policy).
+    OversizedElement,
+    /// Append an annex, which only taproot spends can carry.
+    Annex,
+    /// Give an input without witness some witness data (`WITNESS_UNEXPECTED`).
+    UnexpectedWitness,
+}
+
+impl FromStr for WitnessBreak {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "extra-element" => Ok(Self::ExtraElement),
+            "drop-last" | "drop-last-element" => Ok(Self::DropLastElement),
+            "swap" | "swap-elements" => Ok(Self::SwapElements),
+            "script-mismatch" => Ok(Self::ScriptMismatch),
+            "too-many-elements" => Ok(Self::TooManyElements),
+            "oversized-element" => Ok(Self::OversizedElement),
+            "annex" => Ok(Self::Annex),
+            "unexpected" | "unexpected-witness" => Ok(Self::UnexpectedWitness),
+            _ => Err(format!("Unknown witness break mode '{}'", s)),
+        }
+    }
+}
+
+impl WitnessBreak {
+    /// Whether the mode changes `witness`, which `DropLastElement` and `ScriptMismatch` need
+    /// an element for, `SwapElements` two, and `UnexpectedWitness` none at all.
+    pub fn applies_to(&self, witness: &Witness) -> bool {
+        match self {
+            Self::DropLastElement | Self::ScriptMismatch => !witness.is_empty(),
+            Self::SwapElements => witness.len() >= 2,
+            Self::UnexpectedWitness => witness.is_empty(),
+            _ => true,
+        }
+    }
+}
+
+/// Apply `mode` to the witness of `input`, returning whether it applied.
+///
+/// Elements are added at the bottom of the stack, so the witness script stays last.
+pub fn break_witness(input: &mut TxIn, mode: WitnessBreak) -> bool {
+    if !mode.applies_to(&input.witness) {
+        return false;
+    }
+    let mut items = input.witness.to_vec();
+
+    match mode {
+        WitnessBreak::ExtraElement => items.insert(0, vec![0x01]),
+        WitnessBreak::DropLastElement => {
+            items.pop();
+        }
+        WitnessBreak::SwapElements => items.swap(0, 1),
+        WitnessBreak::ScriptMismatch => {
+            if let Some(last) = items.last_mut() {
+                last.push(OP_NOP.to_u8());
+            }
+        }
+        WitnessBreak::TooManyElements => {
+            let missing = (MAX_STANDARD_P2WSH_STACK_ITEMS + 2).saturating_sub(items.len());
+            items.splice(0..0, vec![vec![]; missing]);
+        }
+        WitnessBreak::OversizedElement => {
+            items.insert(0, vec![0x01; MAX_STANDARD_P2WSH_STACK_ITEM_SIZE + 1])
+        }
+        WitnessBreak::Annex => items.push(vec![ANNEX_TAG]),
+        WitnessBreak::UnexpectedWitness => items.push(vec![0x01]),
+    }
+
+    input.witness = Witness::from_slice(&items);
+    true
+}
@@ -1,6 +1,8 @@
+use std::fmt::Display;
 use std::io;
 use std::io::Write;
-use clap::{Parser, Subcommand};
+use clap::{Args, Parser, Subcommand};
+use serde::Serialize;
 use crate::api::Generator;
 
 #[derive(Parser)]
@@ -26,50 +28,15 @@ pub enum Commands {
     #[command(name = "break-transaction")]
     BreakTransaction {
         raw_transaction: String,
-        #[arg(long, help = "Invalidate transaction version")]
-        version: bool,
-        #[arg(long, help = "Invalidate input transaction ID")]
-        txid: bool,
-        #[arg(long, help = "Invalidate input vout")]
-        vout: bool,
-        #[arg(long = "script-sig", help = "Invalidate input script signature")]
-        script_sig: bool,
-        #[arg(long, help = "Invalidate input sequence number")]
-        sequence: bool,
-        #[arg(long, help = "Invalidate output amount")]
-        amount: bool,
-        #[arg(long = "script-pubkey", help = "Invalidate output script pubkey")]
-        script_pubkey: bool,
-        #[arg(long, help = "Invalidate witness data")]
-        witness: bool,
-        #[arg(long, help = "Invalidate transaction locktime")]
-        locktime: bool,
-        #[arg(long, help = "Invalidate all transaction fields")]
-        all: bool,
+        #[command(flatten)]
+        args: Box<BreakTransactionArgs>,
     },
     #[command(name = "break-block")]
     BreakBlock {
-        block_header: String,
-        #[arg(long, help = "Invalidate block version")]
-        version: bool,
-        #[arg(long = "prev-hash", help = "Invalidate previous block hash")]
-        prev_hash: bool,
-        #[arg(long = "merkle-root", help = "Invalidate merkle root")]
-        merkle_root: bool,
-        #[arg(long, help = "Invalidate timestamp")]
-        timestamp: bool,
-        #[arg(long, help = "Invalidate difficulty bits")]
-        bits: bool,
-        #[arg(long, help = "Invalidate nonce")]
-        nonce: bool,
-        #[arg(long, help = "Invalidate all block fields")]
-        all: bool,
-        #[arg(long, help = "Override version with specific value")]
-        version_override: Option<i32>,
-        #[arg(long, help = "Add/subtract seconds to timestamp")]
-        timestamp_offset: Option<i64>,
-        #[arg(long, help = "Use zero hashes instead of random")]
-        zero_hashes: bool,
+        #[arg(help = "Block header or full serialized block, in hex")]
+        block: String,
+        #[command(flatten)]
+        args: Box<BreakBlockArgs>,
     },
     Tx {
         #[arg(default_value_t = 1)]
@@ -77,8 +44,53 @@ pub enum Commands {
         campuses: Vec<String>,
     },
     Block {
-        #[arg(default_value_t = 1)]
-        txscount: u32,
+        #[arg(help = "Number of transactions, defaults to 1, not allowed with --weight or --sigops")]
+        txscount: Option<u32>,
+        #[arg(long, help = "Fill the block up to this exact weight")]
+        weight: Option<u64>,
+        #[arg(long, help = "Fill the block up to this exact sigop cost")]
+        sigops: Option<u64>,
+        #[arg(long, help = "Print the block as JSON")]
+        json: bool,
+    },
+    #[command(name = "signet-block")]
+    SignetBlock {
+        #[arg(long, help = "Challenge script in hex, defaults to a 1-of-n multisig over the keys")]
+        challenge: Option<String>,
+        #[arg(long = "key", help = "WIF private key able to satisfy the challenge")]
+        keys: Vec<String>,
+        #[arg(long, help = "Compact target in hex, defaults to the signet proof of work limit")]
+        bits: Option<String>,
+        #[arg(long = "break", help = "Corrupt the solution: missing, bad-sig, wrong-key, trailing-data, stale")]
+        break_mode: Option<String>,
+        #[arg(long, help = "Print the block as JSON")]
+        json: bool,
+    },
+    #[command(name = "header-chain")]
+    HeaderChain {
+        #[arg(long, help = "Number of headers, defaults to 11")]
+        length: Option<usize>,
+        #[arg(long = "break", help = "Break the chain: orphan, checkpoint-fork, lower-work, duplicate, non-continuous")]
+        break_mode: Option<String>,
+        #[arg(long, help = "Checkpoint, duplicated or dropped header, defaults to the middle one")]
+        index: Option<usize>,
+        #[arg(long = "prev-blockhash", help = "Hash of the block the chain builds on, e.g. the node's tip")]
+        prev_blockhash: Option<String>,
+        #[arg(long = "start-time", help = "Time of the first header, defaults to ending the chain now")]
+        start_time: Option<u32>,
+        #[arg(long = "prev-times", help = "Comma separated timestamps of the blocks before the chain, oldest first")]
+        prev_times: Option<String>,
+    },
+    #[command(name = "mutate-raw")]
+    MutateRaw {
+        #[arg(help = "Serialized transaction or block, in hex")]
+        raw: String,
+        #[arg(long, help = "Trace the data as a block instead of a transaction")]
+        block: bool,
+        #[arg(long, help = "Field to mutate, as listed by the parse trace")]
+        field: Option<String>,
+        #[arg(long, help = "Mutation: flip, truncate, extend=<hex>, replace=<hex> (default: flip)")]
+        mutation: Option<String>,
     },
     #[command(name = "regtest-start")]
     RegtestStart,
@@ -90,6 +102,183 @@ pub enum Commands {
     },
 }
 
+#[derive(Args)]
+pub struct BreakTransactionArgs {
+    #[arg(long, help = "Invalidate transaction version")]
+    version: bool,
+    #[arg(long, help = "Invalidate input transaction ID")]
+    txid: bool,
+    #[arg(long, help = "Invalidate input vout")]
+    vout: bool,
+    #[arg(long = "script-sig", help = "Invalidate input script signature")]
+    script_sig: bool,
+    #[arg(long, help = "Invalidate input sequence number")]
+    sequence: bool,
+    #[arg(long, help = "Invalidate output amount")]
+    amount: bool,
+    #[arg(long = "script-pubkey", help = "Invalidate output script pubkey")]
+    script_pubkey: bool,
+    #[arg(long, help = "Invalidate witness data")]
+    witness: bool,
+    #[arg(long, help = "Invalidate transaction locktime")]
+    locktime: bool,
+    #[arg(long, help = "Invalidate all transaction fields")]
+    all: bool,
+    #[arg(long = "sig", help = "Break input signatures, may be repeated")]
+    signatures: Vec<String>,
+    #[arg(long = "mutate-script-sig", help = "Mutate input scriptSigs, may be repeated")]
+    script_sig_mutations: Vec<String>,
+    #[arg(long = "mutate-script-pubkey", help = "Mutate output scriptPubKeys, may be repeated")]
+    script_pubkey_mutations: Vec<String>,
+    #[arg(long = "break-witness", help = "Manipulate input witness stacks, may be repeated")]
+    witness_breaks: Vec<String>,
+    #[arg(long = "break-amount", help = "Corrupt output amounts, may be repeated")]
+    amount_breaks: Vec<String>,
+    #[arg(long = "break-structure", help = "Change the shape of the transaction, may be repeated")]
+    structure_breaks: Vec<String>,
+    #[arg(long = "break-serialization", help = "Corrupt the raw encoding, may be repeated")]
+    serialization_breaks: Vec<String>,
+    #[arg(long = "break-finality", help = "Break lock time and sequence rules, may be repeated")]
+    finality_breaks: Vec<String>,
+    #[arg(
+        long = "break-version",
+        allow_negative_numbers = true,
+        allow_hyphen_values = true,
+        help = "Set the version for a given rule, may be repeated"
+    )]
+    version_breaks: Vec<String>,
+    #[arg(long = "input-index", help = "Input touched by the input flags, may be repeated")]
+    input_indices: Vec<usize>,
+    #[arg(long = "output-index", help = "Output touched by the output flags, may be repeated")]
+    output_indices: Vec<usize>,
+    #[arg(long = "prevout-value", help = "Value in satoshis of each spent output, in input order")]
+    prevout_values: Vec<u64>,
+    #[arg(long = "tip-height", help = "Height of the chain tip checked by --break-finality")]
+    tip_height: Option<u32>,
+    #[arg(long = "tip-mtp", help = "Median time past of the chain tip checked by --break-finality")]
+    tip_mtp: Option<u32>,
+    #[arg(long = "prevout-confirmations", help = "Confirmations of each spent output, in input order")]
+    prevout_confirmations: Vec<u32>,
+    #[arg(long = "truc-parent", help = "Unconfirmed outpoint (txid:vout) spent by --break-version truc")]
+    truc_parent: Option<String>,
+}
+
+#[derive(Args)]
+pub struct BreakBlockArgs {
+    #[arg(long, help = "Set a version outdated at --coinbase-height on --network")]
+    version: bool,
+    #[arg(long = "prev-hash", help = "Invalidate previous block hash")]
+    prev_hash: bool,
+    #[arg(long = "merkle-root", help = "Invalidate merkle root")]
+    merkle_root: bool,
+    #[arg(long, help = "Invalidate timestamp")]
+    timestamp: bool,
+    #[arg(long, help = "Invalidate difficulty bits")]
+    bits: bool,
+    #[arg(long, help = "Invalidate nonce")]
+    nonce: bool,
+    #[arg(long, help = "Invalidate all block header fields")]
+    all: bool,
+    #[arg(long = "remove-coinbase", help = "Remove the coinbase transaction")]
+    remove_coinbase: bool,
+    #[arg(long = "duplicate-coinbase", help = "Add a second coinbase transaction")]
+    duplicate_coinbase: bool,
+    #[arg(long = "reorder-txs", help = "Reverse the transactions after the coinbase")]
+    reorder_txs: bool,
+    #[arg(long = "corrupt-tx", help = "Corrupt one transaction of the block")]
+    corrupt_tx: bool,
+    #[arg(long = "duplicate-txs", help = "Duplicate trailing transactions keeping the merkle root")]
+    duplicate_txs: bool,
+    #[arg(long = "64-byte-tx", help = "Append a 64-byte transaction")]
+    sixty_four_byte_tx: bool,
+    #[arg(long = "missing-commitment", help = "Remove the witness commitment")]
+    missing_commitment: bool,
+    #[arg(long = "wrong-commitment", help = "Commit to the wrong witness merkle root")]
+    wrong_commitment: bool,
+    #[arg(long = "misplaced-commitment", help = "Move the witness commitment out of the coinbase")]
+    misplaced_commitment: bool,
+    #[arg(long = "multiple-commitments", help = "Add a second, wrong, witness commitment")]
+    multiple_commitments: bool,
+    #[arg(long = "reserved-value-size", help = "Use a 33 bytes witness reserved value")]
+    reserved_value_size: bool,
+    #[arg(long = "unexpected-witness", help = "Keep witness data without a commitment")]
+    unexpected_witness: bool,
+    #[arg(long = "non-minimal-height", help = "Encode the BIP34 height non-minimally")]
+    non_minimal_height: bool,
+    #[arg(long = "wrong-height", help = "Encode a wrong BIP34 height")]
+    wrong_height: bool,
+    #[arg(long = "missing-height", help = "Remove the BIP34 height")]
+    missing_height: bool,
+    #[arg(long = "short-coinbase", help = "Shrink the coinbase scriptSig below 2 bytes")]
+    short_coinbase: bool,
+    #[arg(long = "long-coinbase", help = "Grow the coinbase scriptSig above 100 bytes")]
+    long_coinbase: bool,
+    #[arg(long = "excessive-coinbase", help = "Pay one satoshi more than subsidy plus fees")]
+    excessive_coinbase: bool,
+    #[arg(long = "coinbase-prevout", help = "Give the coinbase a non-null prevout")]
+    coinbase_prevout: bool,
+    #[arg(long = "immature-spend", help = "Spend a coinbase before maturity")]
+    immature_spend: bool,
+    #[arg(long = "high-hash", help = "Grind a nonce whose hash is just above the target")]
+    high_hash: bool,
+    #[arg(long = "bits-above-limit", help = "Set bits just above the network's pow limit")]
+    bits_above_limit: bool,
+    #[arg(long = "negative-bits", help = "Set the sign bit of the compact target")]
+    negative_bits: bool,
+    #[arg(long = "overflow-bits", help = "Set bits to a target overflowing 256 bits")]
+    overflow_bits: bool,
+    #[arg(long = "zero-bits", help = "Set bits to a zero target")]
+    zero_bits: bool,
+    #[arg(long = "time-at-mtp", help = "Set the time to the median time past of --prev-times")]
+    time_at_mtp: bool,
+    #[arg(long = "time-below-mtp", help = "Set the time one second below the median time past")]
+    time_below_mtp: bool,
+    #[arg(long = "time-too-new", help = "Set the time one second past the two hours limit")]
+    time_too_new: bool,
+    #[arg(long, help = "Set the time past the BIP94 limit before the parent's")]
+    timewarp: bool,
+    #[arg(long = "unknown-deployment", help = "Signal a version bit no deployment has used")]
+    unknown_deployment: bool,
+    #[arg(long = "prev-times", help = "Comma separated ancestor timestamps, oldest first")]
+    prev_times: Option<String>,
+    #[arg(long = "adjusted-time", help = "Network-adjusted time used by --time-too-new")]
+    adjusted_time: Option<u32>,
+    #[arg(long, help = "Network of --bits-above-limit and --version (default: regtest)")]
+    network: Option<String>,
+    #[arg(long = "coinbase-height", help = "Block height used by the coinbase and version breakers")]
+    coinbase_height: Option<u32>,
+    #[arg(long = "coinbase-fees", help = "Fees in satoshis the coinbase may claim")]
+    coinbase_fees: Option<u64>,
+    #[arg(long = "spend-coinbase", help = "Coinbase outpoint (txid:vout) spent by --immature-spend")]
+    spend_coinbase: Option<String>,
+    #[arg(long, help = "Override version with specific value")]
+    version_override: Option<i32>,
+    #[arg(long, help = "Add/subtract seconds to timestamp")]
+    timestamp_offset: Option<i64>,
+    #[arg(long = "prev-hash-override", help = "Previous block hash written by --prev-hash")]
+    prev_hash_override: Option<String>,
+    #[arg(long = "merkle-root-override", help = "Merkle root written by --merkle-root")]
+    merkle_root_override: Option<String>,
+    #[arg(long = "time-override", help = "Absolute time written by --timestamp")]
+    time_override: Option<u32>,
+    #[arg(long = "bits-override", help = "Compact target in hex written by --bits")]
+    bits_override: Option<String>,
+    #[arg(long = "nonce-override", help = "Nonce written by --nonce")]
+    nonce_override: Option<u32>,
+    #[arg(long = "flip-prev-hash", help = "Comma separated bits of the previous hash to flip")]
+    flip_prev_hash: Option<String>,
+    #[arg(long = "flip-merkle-root", help = "Comma separated bits of the merkle root to flip")]
+    flip_merkle_root: Option<String>,
+    #[arg(long, help = "Use zero hashes instead of random")]
+    zero_hashes: bool,
+    #[arg(long = "tx-index", help = "Index of the transaction corrupted by --corrupt-tx")]
+    tx_index: Option<usize>,
+    #[arg(long = "tx-flag", help = "Transaction field corrupted by --corrupt-tx, may be repeated")]
+    tx_flags: Vec<String>,
+    #[arg(long = "recompute-merkle-root", help = "Recompute the merkle root after transaction changes")]
+    recompute_merkle_root: bool,
+}
+
 pub fn handle() {
     let regtest_manager = Generator::regtest_invocation("bitcoinhos", "-regtest");
 
@@ -119,46 +308,36 @@ pub fn handle() {
             Commands::Help => help(),
             Commands::DecodeTransaction { raw_transaction } => transaction_splitter(raw_transaction),
             Commands::DecodeBlock { block_header } => block_splitter(block_header),
-            Commands::BreakTransaction { 
-                raw_transaction, 
-                version, 
-                txid, 
-                vout, 
-                script_sig, 
-                sequence, 
-                amount, 
-                script_pubkey, 
-                witness, 
-                locktime, 
-                all 
-            } => {
-                let flags = build_transaction_flags_vector(
-                    version, txid, vout, script_sig, sequence, 
-                    amount, script_pubkey, witness, locktime, all
-                );
-                break_transaction(raw_transaction, flags);
+            Commands::BreakTransaction { raw_transaction, args } => {
+                let (flags, config) = build_transaction_flags_and_config(&args);
+                break_transaction(raw_transaction, flags, config);
             },
-            Commands::BreakBlock {
-                block_header,
-                version,
-                prev_hash,
-                merkle_root,
-                timestamp,
-                bits,
-                nonce,
-                all,
-                version_override,
-                timestamp_offset,
-                zero_hashes,
-            } => {
-                let (flags, config) = build_block_flags_and_config(
-                    version, prev_hash, merkle_root, timestamp, bits, nonce, all,
-                    version_override, timestamp_offset, zero_hashes
-                );
-                break_block(block_header, flags, config);
+            Commands::BreakBlock { block, args } => {
+                let (flags, config) = build_block_flags_and_config(&args);
+                break_block(block, flags, config);
             },
             Commands::Tx { txscount, .. } => transaction(txscount), // TODO: Implement params into transaction generator
-            Commands::Block { txscount } => block(txscount),
+            Commands::Block { txscount, weight: None, sigops: None, json } => {
+                block(txscount.unwrap_or(1), json)
+            }
+            Commands::Block { txscount: Some(_), .. } => {
+                eprintln!("Error: txscount cannot be combined with --weight or --sigops 🚨")
+            }
+            Commands::Block { weight, sigops, json, .. } => block_with_budget(weight, sigops, json),
+            Commands::SignetBlock { challenge, keys, bits, break_mode, json } => {
+                signet_block(challenge, keys, bits, break_mode, json)
+            }
+            Commands::HeaderChain {
+                length,
+                break_mode,
+                index,
+                prev_blockhash,
+                start_time,
+                prev_times,
+            } => header_chain(length, break_mode, index, prev_blockhash, start_time, prev_times),
+            Commands::MutateRaw { raw, block, field, mutation } => {
+                mutate_raw(raw, block, field, mutation)
+            }
             Commands::Clear => clear(),
             Commands::RegtestStart => handle_result(regtest_manager.start()),
             Commands::RegtestStop => handle_result(regtest_manager.stop()),
@@ -171,74 +350,180 @@ pub fn handle() {
     println!("Program finalized 👋");
 }
 
-fn build_transaction_flags_vector(
-    version: bool, 
-    txid: bool, 
-    vout: bool, 
-    script_sig: bool, 
-    sequence: bool, 
-    amount: bool, 
-    script_pubkey: bool, 
-    witness: bool, 
-    locktime: bool, 
-    all: bool
-) -> Vec<String> {
+fn build_transaction_flags_and_config(args: &BreakTransactionArgs) -> (Vec<String>, Vec<String>) {
     let mut flags = Vec::new();
+    let mut config = Vec::new();
     
-    if all {
+    if args.all {
         flags.push("--all".to_string());
-        return flags;
+    } else {
+        if args.version { flags.push("--version".to_string()); }
+        if args.txid { flags.push("--txid".to_string()); }
+        if args.vout { flags.push("--vout".to_string()); }
+        if args.script_sig { flags.push("--script-sig".to_string()); }
+        if args.sequence { flags.push("--sequence".to_string()); }
+        if args.amount { flags.push("--amount".to_string()); }
+        if args.script_pubkey { flags.push("--script-pubkey".to_string()); }
+        if args.witness { flags.push("--witness".to_string()); }
+        if args.locktime { flags.push("--locktime".to_string()); }
+    }
+    for mode in &args.signatures {
+        flags.push(format!("--sig={}", mode));
+    }
+    for mode in &args.script_sig_mutations {
+        flags.push(format!("--script-sig={}", mode));
+    }
+    for mode in &args.script_pubkey_mutations {
+        flags.push(format!("--script-pubkey={}", mode));
+    }
+    for mode in &args.witness_breaks {
+        flags.push(format!("--witness={}", mode));
+    }
+    for mode in &args.amount_breaks {
+        flags.push(format!("--amount={}", mode));
+    }
+    for mode in &args.structure_breaks {
+        flags.push(format!("--structure={}", mode));
+    }
+    for mode in &args.serialization_breaks {
+        flags.push(format!("--serialization={}", mode));
+    }
+    for mode in &args.finality_breaks {
+        flags.push(format!("--finality={}", mode));
+    }
+    for mode in &args.version_breaks {
+        flags.push(format!("--version={}", mode));
     }
     
-    if version { flags.push("--version".to_string()); }
-    if txid { flags.push("--txid".to_string()); }
-    if vout { flags.push("--vout".to_string()); }
-    if script_sig { flags.push("--script-sig".to_string()); }
-    if sequence { flags.push("--sequence".to_string()); }
-    if amount { flags.push("--amount".to_string()); }
-    if script_pubkey { flags.push("--script-pubkey".to_string()); }
-    if witness { flags.push("--witness".to_string()); }
-    if locktime { flags.push("--locktime".to_string()); }
+    // Configuration options
+    for index in &args.input_indices {
+        config.push(format!("--input-index={}", index));
+    }
+    for index in &args.output_indices {
+        config.push(format!("--output-index={}", index));
+    }
+    for value in &args.prevout_values {
+        config.push(format!("--prevout-value={}", value));
+    }
+    if let Some(height) = args.tip_height {
+        config.push(format!("--tip-height={}", height));
+    }
+    if let Some(time) = args.tip_mtp {
+        config.push(format!("--tip-mtp={}", time));
+    }
+    for confirmations in &args.prevout_confirmations {
+        config.push(format!("--prevout-confirmations={}", confirmations));
+    }
+    if let Some(parent) = &args.truc_parent {
+        config.push(format!("--truc-parent={}", parent));
+    }
     
-    flags
+    (flags, config)
 }
 
-fn build_block_flags_and_config(
-    version: bool,
-    prev_hash: bool,
-    merkle_root: bool,
-    timestamp: bool,
-    bits: bool,
-    nonce: bool,
-    all: bool,
-    version_override: Option<i32>,
-    timestamp_offset: Option<i64>,
-    zero_hashes: bool,
-) -> (Vec<String>, Vec<String>) {
+fn build_block_flags_and_config(args: &BreakBlockArgs) -> (Vec<String>, Vec<String>) {
     let mut flags = Vec::new();
     let mut config = Vec::new();
     
-    if all {
+    if args.all {
         flags.push("--all".to_string());
     } else {
-        if version { flags.push("--version".to_string()); }
-        if prev_hash { flags.push("--prev-hash".to_string()); }
-        if merkle_root { flags.push("--merkle-root".to_string()); }
-        if timestamp { flags.push("--timestamp".to_string()); }
-        if bits { flags.push("--bits".to_string()); }
-        if nonce { flags.push("--nonce".to_string()); }
+        if args.version { flags.push("--version".to_string()); }
+        if args.prev_hash { flags.push("--prev-hash".to_string()); }
+        if args.merkle_root { flags.push("--merkle-root".to_string()); }
+        if args.timestamp { flags.push("--timestamp".to_string()); }
+        if args.bits { flags.push("--bits".to_string()); }
+        if args.nonce { flags.push("--nonce".to_string()); }
     }
+    if args.remove_coinbase { flags.push("--remove-coinbase".to_string()); }
+    if args.duplicate_coinbase { flags.push("--duplicate-coinbase".to_string()); }
+    if args.reorder_txs { flags.push("--reorder-txs".to_string()); }
+    if args.corrupt_tx { flags.push("--corrupt-tx".to_string()); }
+    if args.duplicate_txs { flags.push("--duplicate-txs".to_string()); }
+    if args.sixty_four_byte_tx { flags.push("--64-byte-tx".to_string()); }
+    if args.missing_commitment { flags.push("--missing-commitment".to_string()); }
+    if args.wrong_commitment { flags.push("--wrong-commitment".to_string()); }
+    if args.misplaced_commitment { flags.push("--misplaced-commitment".to_string()); }
+    if args.multiple_commitments { flags.push("--multiple-commitments".to_string()); }
+    if args.reserved_value_size { flags.push("--reserved-value-size".to_string()); }
+    if args.unexpected_witness { flags.push("--unexpected-witness".to_string()); }
+    if args.non_minimal_height { flags.push("--non-minimal-height".to_string()); }
+    if args.wrong_height { flags.push("--wrong-height".to_string()); }
+    if args.missing_height { flags.push("--missing-height".to_string()); }
+    if args.short_coinbase { flags.push("--short-coinbase".to_string()); }
+    if args.long_coinbase { flags.push("--long-coinbase".to_string()); }
+    if args.excessive_coinbase { flags.push("--excessive-coinbase".to_string()); }
+    if args.coinbase_prevout { flags.push("--coinbase-prevout".to_string()); }
+    if args.immature_spend { flags.push("--immature-spend".to_string()); }
+    if args.high_hash { flags.push("--high-hash".to_string()); }
+    if args.bits_above_limit { flags.push("--bits-above-limit".to_string()); }
+    if args.negative_bits { flags.push("--negative-bits".to_string()); }
+    if args.overflow_bits { flags.push("--overflow-bits".to_string()); }
+    if args.zero_bits { flags.push("--zero-bits".to_string()); }
+    if args.time_at_mtp { flags.push("--time-at-mtp".to_string()); }
+    if args.time_below_mtp { flags.push("--time-below-mtp".to_string()); }
+    if args.time_too_new { flags.push("--time-too-new".to_string()); }
+    if args.timewarp { flags.push("--timewarp".to_string()); }
+    if args.unknown_deployment { flags.push("--unknown-deployment".to_string()); }
     
     // Configuration options
-    if let Some(override_val) = version_override {
+    if let Some(override_val) = args.version_override {
         config.push(format!("--version-override={}", override_val));
     }
-    if let Some(offset) = timestamp_offset {
+    if let Some(offset) = args.timestamp_offset {
         config.push(format!("--timestamp-offset={}", offset));
     }
-    if zero_hashes {
+    if let Some(hash) = &args.prev_hash_override {
+        config.push(format!("--prev-hash-override={}", hash));
+    }
+    if let Some(root) = &args.merkle_root_override {
+        config.push(format!("--merkle-root-override={}", root));
+    }
+    if let Some(time) = args.time_override {
+        config.push(format!("--time-override={}", time));
+    }
+    if let Some(bits) = &args.bits_override {
+        config.push(format!("--bits-override={}", bits));
+    }
+    if let Some(nonce) = args.nonce_override {
+        config.push(format!("--nonce-override={}", nonce));
+    }
+    if let Some(times) = &args.prev_times {
+        config.push(format!("--prev-times={}", times));
+    }
+    if let Some(time) = args.adjusted_time {
+        config.push(format!("--adjusted-time={}", time));
+    }
+    if let Some(positions) = &args.flip_prev_hash {
+        config.push(format!("--flip-prev-hash={}", positions));
+    }
+    if let Some(positions) = &args.flip_merkle_root {
+        config.push(format!("--flip-merkle-root={}", positions));
+    }
+    if args.zero_hashes {
         config.push("--zero-hashes".to_string());
     }
+    if let Some(index) = args.tx_index {
+        config.push(format!("--tx-index={}", index));
+    }
+    for flag in &args.tx_flags {
+        config.push(format!("--tx-flag={}", flag));
+    }
+    if args.recompute_merkle_root {
+        config.push("--recompute-merkle-root".to_string());
+    }
+    if let Some(height) = args.coinbase_height {
+        config.push(format!("--coinbase-height={}", height));
+    }
+    if let Some(fees) = args.coinbase_fees {
+        config.push(format!("--coinbase-fees={}", fees));
+    }
+    if let Some(network) = &args.network {
+        config.push(format!("--network={}", network));
+    }
+    if let Some(outpoint) = &args.spend_coinbase {
+        config.push(format!("--spend-coinbase={}", outpoint));
+    }
     
     (flags, config)
 }
@@ -266,24 +551,120 @@ fn help() {
     println!("    --witness         - Invalidate witness data");
     println!("    --locktime        - Invalidate transaction locktime");
     println!("    --all             - Invalidate all transaction fields");
-    println!("\x1b[34mbreak-block <block_header> [FLAGS]\x1b[0m - Break/invalidate specific fields of a block");
+    println!("    --sig <mode>      - Break a signature: high-s, der-padding, negative-r, der-length, sighash,");
+    println!("                        schnorr-r, schnorr-default-sighash, wrong-message");
+    println!("    --mutate-script-sig <mode>     - Mutate scriptSigs: disabled-opcode, op-return, non-minimal-push,");
+    println!("                                     oversized-push, unbalanced-if, too-many-ops, oversized-script");
+    println!("    --mutate-script-pubkey <mode>  - Mutate scriptPubKeys, same modes as --mutate-script-sig");
+    println!("    --break-witness <mode>         - Break witness stacks: extra-element, drop-last, swap, script-mismatch,");
+    println!("                                     too-many-elements, oversized-element, annex, unexpected");
+    println!("    --break-amount <mode>          - Break output amounts: max-money, above-max-money, total-overflow,");
+    println!("                                     negative, zero, dust, exceed-inputs (needs --prevout-value)");
+    println!("    --break-structure <mode>       - Break the transaction shape: no-inputs, no-outputs, duplicate-input,");
+    println!("                                     null-prevout, undersized");
+    println!("    --break-serialization <mode>   - Break the raw encoding: empty-witnesses, wrong-flag, witness-count,");
+    println!("                                     trailing-bytes, non-canonical-varint");
+    println!("    --break-finality <mode>        - Break lock times: height-past-tip, time-past-tip, final-sequences,");
+    println!("                                     relative-unmet, cltv-type, csv-type (no witness inputs)");
+    println!("    --break-version <mode>         - Break the version: zero, above-standard, negative, bip68-v1,");
+    println!("                                     truc (needs --truc-parent), or an exact value such as -3");
+    println!("  Configuration options:");
+    println!("    --input-index <index>   - Input touched by the input flags, may be repeated (default: all)");
+    println!("    --output-index <index>  - Output touched by the output flags, may be repeated (default: all)");
+    println!("    --prevout-value <sats>  - Value of each spent output, in input order");
+    println!("    --tip-height <height>   - Height of the chain tip, required by height-past-tip");
+    println!("    --tip-mtp <time>        - Median time past of the chain tip for --break-finality (default: now)");
+    println!("    --prevout-confirmations <n> - Confirmations of each spent output, in input order (default: 0)");
+    println!("    --truc-parent <txid:vout>   - Unconfirmed outpoint spent as a second parent by the truc mode");
+    println!("\x1b[34mbreak-block <block_header|block> [FLAGS]\x1b[0m - Break/invalidate specific fields of a block");
     println!("  Available flags:");
-    println!("    --version         - Invalidate block version");
+    println!("    --version         - Set a version outdated at the height (BIP34/66/65), see --coinbase-height");
     println!("    --prev-hash       - Invalidate previous block hash");
     println!("    --merkle-root     - Invalidate merkle root");
     println!("    --timestamp       - Invalidate timestamp");
     println!("    --bits            - Invalidate difficulty bits");
     println!("    --nonce           - Invalidate nonce");
-    println!("    --all             - Invalidate all block fields");
+    println!("    --all             - Invalidate all block header fields");
+    println!("    --remove-coinbase    - Remove the coinbase transaction (full block only)");
+    println!("    --duplicate-coinbase - Add a second coinbase transaction (full block only)");
+    println!("    --reorder-txs        - Reverse the transactions after the coinbase (full block only)");
+    println!("    --corrupt-tx         - Corrupt one transaction (full block only)");
+    println!("    --duplicate-txs      - Duplicate trailing transactions, same merkle root (CVE-2012-2459)");
+    println!("    --64-byte-tx         - Append a 64-byte transaction (full block only)");
+    println!("    --missing-commitment   - Remove the witness commitment, keeping witness data");
+    println!("    --wrong-commitment     - Commit to the wrong witness merkle root");
+    println!("    --misplaced-commitment - Move the witness commitment out of the coinbase");
+    println!("    --multiple-commitments - Add a second, wrong, commitment after the real one");
+    println!("    --reserved-value-size  - Use a 33 bytes witness reserved value");
+    println!("    --unexpected-witness   - Keep witness data in a block without commitment");
+    println!("    --non-minimal-height   - Encode the BIP34 height with a trailing zero byte");
+    println!("    --wrong-height         - Encode the BIP34 height off by one");
+    println!("    --missing-height       - Remove the BIP34 height from the coinbase");
+    println!("    --short-coinbase       - Shrink the coinbase scriptSig below 2 bytes");
+    println!("    --long-coinbase        - Grow the coinbase scriptSig above 100 bytes");
+    println!("    --excessive-coinbase   - Pay one satoshi more than subsidy plus fees");
+    println!("    --coinbase-prevout     - Give the coinbase a non-null prevout");
+    println!("    --immature-spend       - Spend a coinbase before its 100 blocks maturity");
+    println!("    --high-hash            - Grind a nonce whose hash is just above the target");
+    println!("    --bits-above-limit     - Set bits just above the network's proof of work limit");
+    println!("    --negative-bits        - Set the sign bit of the compact target");
+    println!("    --overflow-bits        - Set bits to a target overflowing 256 bits");
+    println!("    --zero-bits            - Set bits to a zero target");
+    println!("    --time-at-mtp          - Set the time to the median time past of --prev-times");
+    println!("    --time-below-mtp       - Set the time one second below the median time past");
+    println!("    --time-too-new         - Set the time one second past adjusted time + 2 hours");
+    println!("    --timewarp             - Set the time 601 seconds before the parent's (BIP94, retarget heights)");
+    println!("    --unknown-deployment   - Signal a version bit no deployment has used");
     println!("  Configuration options:");
     println!("    --version-override <value>  - Override version with specific value");
     println!("    --timestamp-offset <secs>   - Add/subtract seconds to timestamp");
+    println!("    --prev-hash-override <hash> - Previous block hash written by --prev-hash");
+    println!("    --merkle-root-override <hash> - Merkle root written by --merkle-root");
+    println!("    --time-override <time>      - Absolute time written by --timestamp");
+    println!("    --bits-override <hex>       - Compact target written by --bits, e.g. 207fffff");
+    println!("    --nonce-override <nonce>    - Nonce written by --nonce");
+    println!("    --prev-times <t1,t2,...>    - Ancestor timestamps, oldest first, for the time rules");
+    println!("    --adjusted-time <time>      - Network-adjusted time for --time-too-new (default: now)");
+    println!("    --flip-prev-hash <bits>     - Flip bits of the previous hash, e.g. 0,255");
+    println!("    --flip-merkle-root <bits>   - Flip bits of the merkle root, e.g. 0,255");
     println!("    --zero-hashes               - Use zero hashes instead of random");
+    println!("    --tx-index <index>          - Transaction corrupted by --corrupt-tx (default: last)");
+    println!("    --tx-flag <flag>            - Field corrupted by --corrupt-tx, e.g. amount (default: all)");
+    println!("    --recompute-merkle-root     - Recompute merkle root so only the targeted rule breaks");
+    println!("    --coinbase-height <height>  - Height used by the coinbase and version breakers (default: from BIP34, required without it)");
+    println!("    --coinbase-fees <sats>      - Fees the coinbase may claim on top of the subsidy");
+    println!("    --spend-coinbase <txid:vout> - Coinbase spent by --immature-spend (default: own)");
+    println!("    --network <network>         - Network for --bits-above-limit and --version (default: regtest)");
+    println!("mutate-raw <hex> [OPTIONS]             - Trace the fields of raw data and mutate the bytes of one");
+    println!("    --block             - Trace the data as a block instead of a transaction");
+    println!("    --field <name>      - Field to mutate, e.g. input[0].script_sig_len (default: only trace)");
+    println!("    --mutation <m>      - flip, truncate, extend=<hex> or replace=<hex> (default: flip)");
     println!("\x1b[32m[Generate]\x1b[0m");
     println!("tx <txscount> [params...]             - Generate one or more transactions");
     println!(
         "block <txscount>                      - Generate new block with one or more transactions"
     );
+    println!("    --json              - Print the serialized block, sizes, weight, sigop cost, txids and wtxids as JSON");
+    println!("  Budget options:");
+    println!("    --weight <wu>       - Fill the block up to an exact weight (e.g. 4000000 or 4000001)");
+    println!("    --sigops <cost>     - Fill the block up to an exact sigop cost (e.g. 80000 or 80004)");
+    println!("                          Only legacy sigops are placed, so the cost is a multiple of 4");
+    println!("                          and 80004 is the closest cost above the limit");
+    println!("signet-block [OPTIONS]                 - Generate a signed and mined signet block");
+    println!("  Signet options:");
+    println!("    --challenge <hex>   - Challenge script, defaults to a 1-of-n multisig over the keys");
+    println!("    --key <wif>         - Private key signing the block, may be repeated");
+    println!("    --bits <hex>        - Compact target to mine against (e.g. 207fffff)");
+    println!("    --break <mode>      - Corrupt the solution: missing, bad-sig, wrong-key, trailing-data, stale");
+    println!("    --json              - Print the block, challenge and solution as JSON");
+    println!("header-chain [OPTIONS]                 - Generate a chain of mined regtest headers");
+    println!("  Chain options:");
+    println!("    --length <n>        - Number of headers (default: 11)");
+    println!("    --break <mode>      - Break the chain: orphan, checkpoint-fork, lower-work, duplicate, non-continuous");
+    println!("    --index <i>         - Checkpoint, duplicated or dropped header (default: middle)");
+    println!("    --prev-blockhash <hash>     - Block the chain builds on, e.g. the regtest genesis or tip");
+    println!("    --start-time <t>            - Time of the first header (default: chain ends now)");
+    println!("    --prev-times <t1,t2,...>    - Timestamps of the blocks before the chain, oldest first");
     println!("\x1b[32m[Regtest]\x1b[0m");
     println!(
         "get-blockby-height <height>           - Get a block at specific height in the regtest"
@@ -323,24 +704,24 @@ fn block_splitter(block_header: String) {
     }
 }
 
-fn break_transaction(raw_transaction: String, flags: Vec<String>) {
+fn break_transaction(raw_transaction: String, flags: Vec<String>, config: Vec<String>) {
     if flags.is_empty() {
         println!("No invalidation flags specified. Use 'help' for usage information.");
         return;
     }
     
-    let result = Generator::break_transaction(raw_transaction, flags);
+    let result = Generator::break_transaction(raw_transaction, flags, config);
     println!("🔨 Transaction Breaking Result:");
     println!("{}", result);
 }
 
-fn break_block(block_header: String, flags: Vec<String>, config: Vec<String>) {
+fn break_block(block: String, flags: Vec<String>, config: Vec<String>) {
     if flags.is_empty() {
         println!("No invalidation flags specified. Use 'help' for usage information.");
         return;
     }
     
-    let result = Generator::break_block(block_header, flags, config);
+    let result = Generator::break_block(block, flags, config);
     println!("🔨 Block Breaking Result:");
     println!("{}", result);
 }
@@ -350,9 +731,53 @@ fn transaction(txscount: u32) {
     println!("Transactions: {}", transactions);
 }
 
-fn block(txscount: u32) {
-    let block = Generator::block(txscount);
-    println!("Block: {}", block);
+fn block(txscount: u32, json: bool) {
+    print_block(&Generator::block(txscount), json);
+}
+fn block_with_budget(weight: Option<u64>, sigops: Option<u64>, json: bool) {
+    match Generator::block_with_budget(weight, sigops) {
+        Ok(block) => print_block(&block, json),
+        Err(e) => eprintln!("Error: {} 🚨", e),
+    }
+}
+fn signet_block(
+    challenge: Option<String>,
+    keys: Vec<String>,
+    bits: Option<String>,
+    break_mode: Option<String>,
+    json: bool,
+) {
+    match Generator::signet_block(challenge, keys, bits, break_mode) {
+        Ok(block) => print_block(&block, json),
+        Err(e) => eprintln!("Error: {} 🚨", e),
+    }
+}
+fn print_block<T: Serialize + Display>(block: &T, json: bool) {
+    if json {
+        match serde_json::to_string_pretty(block) {
+            Ok(json) => println!("{}", json),
+            Err(e) => eprintln!("Error: {} 🚨", e),
+        }
+    } else {
+        println!("{}", block);
+    }
+}
+fn header_chain(
+    length: Option<usize>,
+    break_mode: Option<String>,
+    index: Option<usize>,
+    prev_blockhash: Option<String>,
+    start_time: Option<u32>,
+    prev_times: Option<String>,
+) {
+    let chain =
+        Generator::header_chain(length, break_mode, index, prev_blockhash, start_time, prev_times);
+    println!("Header chain: {}", chain);
+}
+fn mutate_raw(raw: String, block: bool, field: Option<String>, mutation: Option<String>) {
+    let result = Generator::mutate_raw(raw, block, field, mutation);
+    println!("🔨 Raw Mutation Result:");
+    println!("{}", result);
 }
 fn clear() {
     print!("\x1B[2J\x1B[1;1H"); 
@@ -1,5 +1,6 @@
 mod cli;
 mod api;
+#[allow(clippy::module_inception, clippy::bool_assert_comparison)]
 mod tests;
 fn main() {
     cli::handle();
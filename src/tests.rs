@@ -3,6 +3,34 @@
 mod tests {
     pub use crate::api::{Generator};
 
+    use bitcoin::Transaction;
+    use misfit_core::transaction::generator::GenerateTx;
+    use misfit_core::transaction::random::transaction::TxParams;
+
+    /// A random valid transaction.
+    fn random_tx() -> Transaction {
+        GenerateTx::valid_random(TxParams::default())
+    }
+
+    /// Raw hex printed by `Generator::break_transaction` for `tx` broken with `flags`.
+    fn break_transaction_hex(tx: &Transaction, flags: &[&str], config: &[&str]) -> String {
+        let result = Generator::break_transaction(
+            bitcoin::consensus::encode::serialize_hex(tx),
+            flags.iter().map(|flag| flag.to_string()).collect(),
+            config.iter().map(|option| option.to_string()).collect(),
+        );
+        let Some(hex) = result.split("Invalidated Raw Transaction:\n").nth(1) else {
+            panic!("no raw transaction in {}", result);
+        };
+        hex.trim().trim_matches('"').to_string()
+    }
+
+    /// `tx` broken through `Generator::break_transaction` with `flags`, decoded back.
+    fn break_and_decode(tx: &Transaction, flags: &[&str], config: &[&str]) -> Transaction {
+        bitcoin::consensus::encode::deserialize_hex(&break_transaction_hex(tx, flags, config))
+            .unwrap()
+    }
+
     #[test]
     fn test_generate_single_transaction() {
         let result = Generator::transaction(1);
@@ -47,35 +75,67 @@ mod tests {
         assert!(result.contains("TXIDs:"));
     }
 
-      #[test]
+    #[test]
     fn test_generate_one_block_with_one_transaction() {
         let result = Generator::block(1);
-        let sections: Vec<&str> = result.split("\n---\n").collect();
-        assert_eq!(sections.len(), 4); // <-- Ajuste aqui (antes era 3)
-        assert!(result.contains("Header"));
-        assert!(result.contains("Raw txs:"));
-        assert!(result.contains("TxID:"));
+        // Coinbase plus the requested transaction.
+        assert_eq!(result.txids.len(), 2);
+        assert_eq!(result.wtxids.len(), 2);
+        assert!(result.hex.starts_with(&result.header));
     }
-      #[test]
+
+    #[test]
     fn generate_zero_tx_block() {
         let result = Generator::block(0);
-        let sections: Vec<&str> = result.split("\n---\n").collect();
-        assert_eq!(sections.len(), 4); // <-- Ajuste aqui (antes era 3)
-        assert!(result.contains("Header"));
-        assert!(result.contains("Raw txs:"));
-        assert!(result.contains("TxID:"));  
+        assert_eq!(result.txids.len(), 1);
+        assert_eq!(result.wtxids.len(), 1);
     }
 
     #[test]
     fn test_generate_block_with_multiple_transactions() {
+        use bitcoin::consensus::encode;
+        use bitcoin::Block;
+
         let tx_count = 10;
         let result = Generator::block(tx_count);
-        assert!(result.contains("Raw txs:"));
-        assert!(result.contains("TxID:"));
-        let sections: Vec<&str> = result.split("\n---\n").collect();
-        assert_eq!(sections.len(), 4); // <-- Ajuste aqui (antes era 3)
+        assert_eq!(result.txids.len(), tx_count as usize + 1);
+
+        // The serialized block round-trips and matches every reported figure.
+        let block: Block = encode::deserialize_hex(&result.hex).unwrap();
+        assert_eq!(block.block_hash().to_string(), result.hash);
+        assert_eq!(block.total_size(), result.size);
+        assert_eq!(block.weight().to_wu(), result.weight);
+        assert_eq!(result.weight, result.stripped_size as u64 * 3 + result.size as u64);
+        assert!(result.stripped_size < result.size);
+
+        let wtxids: Vec<String> = block
+            .txdata
+            .iter()
+            .map(|tx| tx.compute_wtxid().to_string())
+            .collect();
+        assert_eq!(wtxids, result.wtxids);
+        assert_ne!(result.txids[1], result.wtxids[1]);
     }
 
+    #[test]
+    fn test_budget_and_signet_blocks_report_block_info() {
+        let result = Generator::block_with_budget(None, Some(80_004)).unwrap();
+        assert_eq!(result.sigop_cost, 80_004);
+        let json: serde_json::Value =
+            serde_json::from_str(&serde_json::to_string(&result).unwrap()).unwrap();
+        assert_eq!(json["sigop_cost"], 80_004);
+        assert!(Generator::block_with_budget(None, Some(80_001)).is_err());
+
+        let result =
+            Generator::signet_block(None, vec![], Some("207fffff".to_string()), None).unwrap();
+        assert!(!result.challenge.is_empty());
+        assert!(!result.solution.is_empty());
+        let json: serde_json::Value =
+            serde_json::from_str(&serde_json::to_string(&result).unwrap()).unwrap();
+        assert_eq!(json["challenge"], result.challenge);
+        assert_eq!(json["hex"], result.block.hex);
+        assert!(Generator::signet_block(None, vec!["nope".to_string()], None, None).is_err());
+    }
 
     #[test]
     fn test_parse_cli_flags_to_invalidation_flags() {
@@ -203,7 +263,7 @@ mod tests {
         let tx_result = Generator::transaction(1);
 
         let cli_flags = vec!["--version".to_string()];
-        let result = Generator::break_transaction(tx_result.to_string(), cli_flags);
+        let result = Generator::break_transaction(tx_result.to_string(), cli_flags, vec![]);
         
         assert!(result != tx_result);
     }
@@ -214,7 +274,7 @@ mod tests {
         let raw_tx = "4f6e3b7201e8370e51a135fb8e468e8188ea580b5a6c74a92b5cab5af2785bd307297be9a808e47956006b6b5dbe0118a478e14edc0b651976a9148840c86761418aa78e7667e8e7e427c4e955989588ac59500852".to_string();
         let cli_flags = vec![];
         
-        let result = Generator::break_transaction(raw_tx, cli_flags);
+        let result = Generator::break_transaction(raw_tx, cli_flags, vec![]);
         
         assert!(result.contains("No invalidation flags specified"));
         assert!(result.contains("Use 'help' for usage information"));
@@ -356,4 +416,1499 @@ mod tests {
         }
 }
 }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_timestamp_modes_follow_median_time_past() {
+        use misfit_core::block::random::timestamp::{
+            Timestamp, TimestampMode, TimestampParams, MAX_FUTURE_BLOCK_TIME,
+        };
+
+        // Only the last 11 timestamps are considered, unordered on purpose.
+        let prev_times: Vec<u32> = vec![1, 2, 100, 90, 110, 80, 120, 70, 130, 60, 140, 50];
+        assert_eq!(Timestamp::median_time_past(&prev_times), Some(90));
+        assert_eq!(Timestamp::median_time_past(&[]), None);
+
+        let adjusted_time = 1_000;
+        let params = |mode| TimestampParams {
+            prev_times: Some(prev_times.clone()),
+            adjusted_time: Some(adjusted_time),
+            mode: Some(mode),
+        };
+
+        assert_eq!(Timestamp::random(params(TimestampMode::MedianTimePast)).unwrap(), 90);
+        assert_eq!(
+            Timestamp::random(params(TimestampMode::MaxFuture)).unwrap(),
+            adjusted_time + MAX_FUTURE_BLOCK_TIME
+        );
+
+        for _ in 0..20 {
+            let time = Timestamp::random(params(TimestampMode::Valid)).unwrap();
+            assert!(time > 90 && time <= adjusted_time + MAX_FUTURE_BLOCK_TIME);
+        }
+
+        // A chain ahead of the clock leaves no valid timestamp.
+        let ahead = TimestampParams {
+            prev_times: Some(vec![adjusted_time + MAX_FUTURE_BLOCK_TIME; 11]),
+            adjusted_time: Some(adjusted_time),
+            mode: Some(TimestampMode::Valid),
+        };
+        assert!(Timestamp::random(ahead).is_err());
+    }
+
+    #[test]
+    fn test_header_chain_is_linked_with_valid_pow() {
+        use misfit_core::block::generator::GenerateBlock;
+        use misfit_core::block::random::chain::ChainParams;
+        use misfit_core::block::random::timestamp::Timestamp;
+
+        let chain = GenerateBlock::header_chain(ChainParams {
+            length: Some(20),
+            prev_times: Some(vec![u32::MAX / 2; 11]),
+            ..Default::default()
+        }).unwrap();
+
+        assert_eq!(chain.headers.len(), 20);
+        let times = chain.times();
+        for (i, header) in chain.headers.iter().enumerate() {
+            assert!(header.target().is_met_by(header.block_hash()));
+            if i > 0 {
+                assert_eq!(header.prev_blockhash, chain.headers[i - 1].block_hash());
+            }
+            // Every header must be above the MTP of its ancestors.
+            let position = chain.prev_times.len() + i;
+            assert!(header.time > Timestamp::median_time_past(&times[..position]).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_block_budget_hits_exact_weight_and_sigops() {
+        use misfit_core::block::generator::GenerateBlock;
+        use misfit_core::block::random::budget::{
+            BlockFiller, BudgetParams, MAX_BLOCK_SIGOPS_COST, MAX_BLOCK_WEIGHT,
+        };
+
+        for weight in [MAX_BLOCK_WEIGHT, MAX_BLOCK_WEIGHT + 1] {
+            let block = GenerateBlock::with_budget(BudgetParams {
+                weight: Some(weight),
+                ..Default::default()
+            })
+            .unwrap();
+            assert_eq!(block.weight().to_wu(), weight);
+            assert!(block.check_merkle_root());
+            assert!(block.check_witness_commitment());
+            assert!(block.header.validate_pow(block.header.target()).is_ok());
+            assert_eq!(
+                block.header.target(),
+                bitcoin::params::Params::REGTEST.max_attainable_target
+            );
+        }
+
+        for sigops in [MAX_BLOCK_SIGOPS_COST, MAX_BLOCK_SIGOPS_COST + 4] {
+            let block = GenerateBlock::with_budget(BudgetParams {
+                sigops: Some(sigops),
+                weight: Some(MAX_BLOCK_WEIGHT),
+                ..Default::default()
+            })
+            .unwrap();
+            assert_eq!(BlockFiller::legacy_sigop_cost(&block.txdata), sigops);
+            assert_eq!(block.weight().to_wu(), MAX_BLOCK_WEIGHT);
+        }
+
+        assert!(GenerateBlock::with_budget(BudgetParams {
+            sigops: Some(MAX_BLOCK_SIGOPS_COST + 1),
+            ..Default::default()
+        })
+        .is_err());
+    }
+
+    #[test]
+    fn test_signet_block_carries_a_valid_solution() {
+        use bitcoin::secp256k1::{Message, Secp256k1};
+        use bitcoin::sighash::{EcdsaSighashType, SighashCache};
+        use bitcoin::{CompactTarget, NetworkKind, PrivateKey, PublicKey, ScriptBuf};
+        use misfit_core::block::generator::GenerateBlock;
+        use misfit_core::block::random::header::HeaderParams;
+        use misfit_core::block::signet::{Signet, SignetParams};
+        use misfit_core::breakers::block::{SignetBreak, SignetProcessor};
+
+        let secp = Secp256k1::new();
+        let key = PrivateKey::generate(NetworkKind::Test);
+        let pubkey = PublicKey::from_private_key(&secp, &key);
+        let challenge = ScriptBuf::new_p2wpkh(&pubkey.wpubkey_hash().unwrap());
+
+        let (block, challenge) = GenerateBlock::signet(SignetParams {
+            challenge: Some(challenge),
+            private_keys: Some(vec![key]),
+            header: Some(HeaderParams {
+                bits: Some(CompactTarget::from_consensus(0x207fffff)),
+                ..Default::default()
+            }),
+            ..Default::default()
+        })
+        .unwrap();
+
+        assert!(block.header.target().is_met_by(block.block_hash()));
+        assert!(block.check_merkle_root());
+        assert!(block.check_witness_commitment());
+
+        // The witness signs the BIP325 spending transaction for our key.
+        let solution = Signet::solution(&block).unwrap();
+        let (script_sig, witness) = Signet::decode_solution(&solution).unwrap();
+        assert!(script_sig.is_empty());
+        assert_eq!(witness.nth(1).unwrap(), pubkey.to_bytes());
+
+        let (to_spend, to_sign) = Signet::signet_txs(&block, &challenge);
+        let sighash = SighashCache::new(&to_sign)
+            .p2wpkh_signature_hash(0, &challenge, to_spend.output[0].value, EcdsaSighashType::All)
+            .unwrap();
+        let signature = bitcoin::ecdsa::Signature::from_slice(witness.nth(0).unwrap()).unwrap();
+        secp.verify_ecdsa(&Message::from(sighash), &signature.signature, &pubkey.inner)
+            .unwrap();
+
+        for mode in ["missing", "bad-sig", "wrong-key", "trailing-data", "stale"] {
+            let mode: SignetBreak = mode.parse().unwrap();
+            let broken = SignetProcessor::process_signet(&block, mode, &challenge).unwrap();
+
+            assert!(broken.header.target().is_met_by(broken.block_hash()));
+            assert_ne!(broken.block_hash(), block.block_hash());
+            if mode != SignetBreak::StaleSolution {
+                assert_ne!(Signet::solution(&broken), Some(solution.clone()));
+            }
+        }
+    }
+
+    #[test]
+    fn test_version_bits_modes_and_deployment_schedule() {
+        use bitcoin::Network;
+        use misfit_core::block::generator::GenerateBlock;
+        use misfit_core::block::random::chain::ChainParams;
+        use misfit_core::block::random::version::{VersionBits, VersionMode, VersionParams};
+
+        let version = VersionBits::random(VersionParams {
+            deployments: Some(vec![1, 2]),
+            ..Default::default()
+        })
+        .unwrap();
+        assert!(VersionBits::has_top_bits(version));
+        assert!(version.is_signalling_soft_fork(1) && version.is_signalling_soft_fork(2));
+        assert!(!version.is_signalling_soft_fork(0));
+
+        // Mainnet activation heights of BIP34, BIP66 and BIP65.
+        for (height, minimum) in [(227_930, 1), (227_931, 2), (363_725, 3), (388_381, 4)] {
+            let version = VersionBits::random(VersionParams {
+                mode: Some(VersionMode::Minimum),
+                height: Some(height),
+                network: Some(Network::Bitcoin),
+                ..Default::default()
+            })
+            .unwrap();
+            assert_eq!(version.to_consensus(), minimum);
+
+            let outdated = VersionBits::random(VersionParams {
+                mode: Some(VersionMode::Outdated),
+                height: Some(height),
+                network: Some(Network::Bitcoin),
+                ..Default::default()
+            });
+            if minimum > 1 {
+                let outdated = outdated.unwrap().to_consensus();
+                assert!(outdated >= 1 && outdated < minimum);
+            } else {
+                // Before BIP34 no version is rejected.
+                assert!(outdated.is_err());
+            }
+        }
+
+        // One period reaching the regtest threshold of 108 signals out of 144, one missing it.
+        let versions = VersionBits::schedule(3, &[108, 107], Network::Regtest);
+        assert_eq!(versions.len(), 288);
+        let signals = |period: &[bitcoin::block::Version]| {
+            period.iter().filter(|v| v.is_signalling_soft_fork(3)).count()
+        };
+        assert_eq!(signals(&versions[..144]), 108);
+        assert_eq!(signals(&versions[144..]), 107);
+
+        let chain = GenerateBlock::header_chain(ChainParams {
+            length: Some(versions.len()),
+            versions: Some(versions.clone()),
+            ..Default::default()
+        }).unwrap();
+        let chain_versions: Vec<_> = chain.headers.iter().map(|header| header.version).collect();
+        assert_eq!(chain_versions, versions);
+    }
+
+    #[test]
+    fn test_break_full_block_transaction_fields() {
+        use bitcoin::consensus::encode;
+        use bitcoin::Block;
+        use misfit_core::block::generator::GenerateBlock;
+        use misfit_core::block::random::block::BlockParams;
+        use misfit_core::breakers::block::{BlockBreaker, BlockField, ProcessingConfig};
+        use misfit_core::transaction::generator::GenerateTx;
+        use misfit_core::transaction::random::transaction::TxParams;
+
+        let txs = (0..3).map(|_| GenerateTx::valid_random(TxParams::default())).collect();
+        let block = GenerateBlock::valid_random(BlockParams {
+            header: None,
+            txs: Some(txs),
+        });
+        let break_with = |field: BlockField| {
+            BlockBreaker::break_with_config(
+                &block,
+                ProcessingConfig {
+                    fields_to_modify: vec![field],
+                    corrupt_tx_index: Some(2),
+                    recompute_merkle_root: true,
+                    ..Default::default()
+                },
+            ).unwrap()
+        };
+
+        let broken = break_with(BlockField::RemoveCoinbase);
+        assert_eq!(broken.txdata.len(), 3);
+        assert!(!broken.txdata[0].is_coinbase());
+
+        let broken = break_with(BlockField::DuplicateCoinbase);
+        assert!(broken.txdata[0].is_coinbase() && broken.txdata[1].is_coinbase());
+        assert_ne!(broken.txdata[0].compute_txid(), broken.txdata[1].compute_txid());
+
+        let broken = break_with(BlockField::ReorderTransactions);
+        assert_eq!(broken.txdata[1], block.txdata[3]);
+        assert_eq!(broken.txdata[3], block.txdata[1]);
+
+        let broken = break_with(BlockField::CorruptTransaction);
+        assert_ne!(broken.txdata[2], block.txdata[2]);
+        assert_eq!(broken.txdata[3], block.txdata[3]);
+
+        let out_of_range = ProcessingConfig {
+            fields_to_modify: vec![BlockField::CorruptTransaction],
+            corrupt_tx_index: Some(4),
+            ..Default::default()
+        };
+        assert!(BlockBreaker::break_with_config(&block, out_of_range).is_err());
+
+        // Only the targeted rule is violated, the header still commits to the transactions.
+        for field in [
+            BlockField::RemoveCoinbase,
+            BlockField::DuplicateCoinbase,
+            BlockField::ReorderTransactions,
+            BlockField::CorruptTransaction,
+        ] {
+            let broken = break_with(field);
+            assert!(broken.check_merkle_root());
+            assert_eq!(broken.header.time, block.header.time);
+        }
+
+        let result = Generator::break_block(
+            encode::serialize_hex(&block),
+            vec!["--corrupt-tx".to_string()],
+            vec!["--tx-flag=amount".to_string(), "--tx-index=1".to_string()],
+        );
+        assert!(result.contains("Corrupt Transaction"));
+        let hex = result.split("Broken Block (Hex):\n").nth(1).unwrap().trim();
+        let broken: Block = encode::deserialize_hex(hex).unwrap();
+        assert_ne!(broken.txdata[1].output, block.txdata[1].output);
+        assert_eq!(broken.txdata[1].input, block.txdata[1].input);
+        assert!(!broken.check_merkle_root());
+    }
+
+    #[test]
+    fn test_merkle_malleation_keeps_the_root() {
+        use misfit_core::block::generator::GenerateBlock;
+        use misfit_core::block::random::block::BlockParams;
+        use misfit_core::breakers::block::{BlockBreaker, BlockField, MerkleRootProcessor};
+        use misfit_core::transaction::generator::GenerateTx;
+        use misfit_core::transaction::random::transaction::TxParams;
+
+        assert_eq!(MerkleRootProcessor::odd_level(1), None);
+        assert_eq!(MerkleRootProcessor::odd_level(4), None);
+        assert_eq!(MerkleRootProcessor::odd_level(3), Some(0));
+        assert_eq!(MerkleRootProcessor::odd_level(6), Some(1));
+        assert_eq!(MerkleRootProcessor::odd_level(12), Some(2));
+
+        for tx_count in [3, 6, 12] {
+            let txs = (1..tx_count).map(|_| GenerateTx::valid_random(TxParams::default())).collect();
+            let block = GenerateBlock::valid_random(BlockParams {
+                header: None,
+                txs: Some(txs),
+            });
+
+            let mutated = BlockBreaker::break_single_field(&block, BlockField::DuplicateTransactions).unwrap();
+            assert!(mutated.txdata.len() > block.txdata.len());
+            assert_eq!(mutated.compute_merkle_root(), block.compute_merkle_root());
+            assert_eq!(mutated.block_hash(), block.block_hash());
+        }
+
+        // Four transactions have no odd level, the failure reaches the caller.
+        let txs = (1..4).map(|_| GenerateTx::valid_random(TxParams::default())).collect();
+        let block = GenerateBlock::valid_random(BlockParams {
+            header: None,
+            txs: Some(txs),
+        });
+        assert!(BlockBreaker::break_single_field(&block, BlockField::DuplicateTransactions).is_err());
+        let result = Generator::break_block(
+            bitcoin::consensus::encode::serialize_hex(&block),
+            vec!["--duplicate-txs".to_string()],
+            vec![],
+        );
+        assert!(result.starts_with("Error breaking block"), "{}", result);
+
+        let tx = MerkleRootProcessor::sixty_four_byte_transaction();
+        assert_eq!(bitcoin::consensus::encode::serialize(&tx).len(), 64);
+        assert!(MerkleRootProcessor::duplicate_transactions(&bitcoin::Block {
+            header: bitcoin::constants::genesis_block(bitcoin::Network::Regtest).header,
+            txdata: vec![tx.clone(), tx],
+        })
+        .is_err());
+    }
+
+    #[test]
+    fn test_witness_commitment_breakers() {
+        use bitcoin::Block;
+        use misfit_core::block::generator::GenerateBlock;
+        use misfit_core::breakers::block::{BlockBreaker, BlockField, ProcessingConfig};
+        use misfit_core::transaction::generator::GenerateTx;
+        use misfit_core::transaction::random::coinbase::{witness_commitment_index, CoinbaseParams};
+        use misfit_core::transaction::random::transaction::TxParams;
+
+        let mut block = Block {
+            header: bitcoin::constants::genesis_block(bitcoin::Network::Regtest).header,
+            txdata: vec![
+                GenerateTx::coinbase(CoinbaseParams {
+                    witness_commitment: Some(true),
+                    ..Default::default()
+                }),
+                GenerateTx::valid_random(TxParams::default()),
+            ],
+        };
+        GenerateBlock::recommit(&mut block);
+        block.header = GenerateBlock::mine(block.header).unwrap();
+        assert!(block.check_witness_commitment());
+
+        for field in [
+            BlockField::MissingWitnessCommitment,
+            BlockField::WrongWitnessCommitment,
+            BlockField::MisplacedWitnessCommitment,
+            BlockField::MultipleWitnessCommitments,
+            BlockField::WrongWitnessReservedValueSize,
+            BlockField::UnexpectedWitness,
+        ] {
+            let broken = BlockBreaker::break_with_config(
+                &block,
+                ProcessingConfig {
+                    fields_to_modify: vec![field.clone()],
+                    recompute_merkle_root: true,
+                    ..Default::default()
+                },
+            ).unwrap();
+            assert!(!broken.check_witness_commitment(), "{:?}", field);
+            assert!(broken.check_merkle_root(), "{:?}", field);
+            // Mined again, so the commitment is the only rule broken.
+            assert!(broken.header.validate_pow(broken.header.target()).is_ok(), "{:?}", field);
+
+            let coinbase = &broken.txdata[0];
+            match field {
+                BlockField::MissingWitnessCommitment | BlockField::MisplacedWitnessCommitment => {
+                    assert_eq!(witness_commitment_index(coinbase), None)
+                }
+                BlockField::MultipleWitnessCommitments => {
+                    assert_eq!(coinbase.output.len(), block.txdata[0].output.len() + 1)
+                }
+                BlockField::WrongWitnessReservedValueSize => {
+                    assert_eq!(coinbase.input[0].witness.nth(0).unwrap().len(), 33)
+                }
+                BlockField::UnexpectedWitness => {
+                    assert!(coinbase.input[0].witness.is_empty());
+                    assert!(broken.txdata[1..]
+                        .iter()
+                        .any(|tx| tx.input.iter().any(|input| !input.witness.is_empty())));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    #[test]
+    fn test_coinbase_breakers() {
+        use bitcoin::{Amount, Block};
+        use misfit_core::breakers::block::{
+            BlockBreaker, BlockField, CoinbaseProcessor, ProcessingConfig,
+        };
+        use misfit_core::block::generator::GenerateBlock;
+        use misfit_core::transaction::generator::GenerateTx;
+        use misfit_core::transaction::random::coinbase::{block_subsidy, CoinbaseParams};
+
+        let height = 300;
+        let mut block = Block {
+            header: bitcoin::constants::genesis_block(bitcoin::Network::Regtest).header,
+            txdata: vec![GenerateTx::coinbase(CoinbaseParams {
+                height: Some(height),
+                witness_commitment: Some(true),
+                ..Default::default()
+            })],
+        };
+        GenerateBlock::recommit(&mut block);
+        block.header = GenerateBlock::mine(block.header).unwrap();
+        assert_eq!(CoinbaseProcessor::height(&block), Some(height));
+        let break_with = |field: BlockField| {
+            BlockBreaker::break_with_config(
+                &block,
+                ProcessingConfig {
+                    fields_to_modify: vec![field],
+                    coinbase_fees: Some(1_000),
+                    recompute_merkle_root: true,
+                    ..Default::default()
+                },
+            ).unwrap()
+        };
+        let script_sig = |block: &Block| block.txdata[0].input[0].script_sig.clone();
+
+        // 300 is pushed as 0x2c01, non-minimally as 0x2c0100.
+        let broken = break_with(BlockField::NonMinimalHeight);
+        assert!(script_sig(&broken).as_bytes().starts_with(&[0x03, 0x2c, 0x01, 0x00]));
+        assert_eq!(CoinbaseProcessor::height(&broken), Some(height));
+        assert!(broken.bip34_block_height().is_err());
+
+        let broken = break_with(BlockField::WrongHeight);
+        assert_eq!(CoinbaseProcessor::height(&broken), Some(height + 1));
+
+        let broken = break_with(BlockField::MissingHeight);
+        assert_ne!(CoinbaseProcessor::height(&broken), Some(height));
+        assert!(script_sig(&broken).len() >= 2);
+
+        assert_eq!(script_sig(&break_with(BlockField::ShortCoinbaseScriptSig)).len(), 1);
+        let broken = break_with(BlockField::LongCoinbaseScriptSig);
+        assert_eq!(script_sig(&broken).len(), 101);
+        assert_eq!(CoinbaseProcessor::height(&broken), Some(height));
+
+        let broken = break_with(BlockField::ExcessiveCoinbaseValue);
+        let paid: Amount = broken.txdata[0].output.iter().map(|output| output.value).sum();
+        assert_eq!(paid, block_subsidy(height) + Amount::from_sat(1_001));
+
+        let broken = break_with(BlockField::NonNullCoinbasePrevout);
+        assert!(!broken.txdata[0].is_coinbase());
+
+        let broken = break_with(BlockField::ImmatureCoinbaseSpend);
+        let spent = broken.txdata[1].input[0].previous_output;
+        assert_eq!(spent.txid, broken.txdata[0].compute_txid());
+        assert!(broken.check_witness_commitment());
+
+        for field in [
+            BlockField::WrongHeight,
+            BlockField::ExcessiveCoinbaseValue,
+            BlockField::ImmatureCoinbaseSpend,
+        ] {
+            let broken = break_with(field);
+            assert!(broken.check_merkle_root());
+            assert!(broken.header.validate_pow(broken.header.target()).is_ok());
+        }
+
+        // Fees overflowing an amount, or a coinbase without a height, are reported.
+        let overflowing = ProcessingConfig {
+            fields_to_modify: vec![BlockField::ExcessiveCoinbaseValue],
+            coinbase_fees: Some(u64::MAX),
+            ..Default::default()
+        };
+        assert!(BlockBreaker::break_with_config(&block, overflowing).is_err());
+        let mut overpaying = block.clone();
+        let mut output = overpaying.txdata[0].output[0].clone();
+        output.value = block_subsidy(height) + Amount::from_sat(1_002);
+        overpaying.txdata[0].output.push(output);
+        let config = ProcessingConfig {
+            fields_to_modify: vec![BlockField::ExcessiveCoinbaseValue],
+            coinbase_fees: Some(1_000),
+            ..Default::default()
+        };
+        assert!(BlockBreaker::break_with_config(&overpaying, config).is_err());
+        let mut no_height = block.clone();
+        no_height.txdata[0].input[0].script_sig = bitcoin::ScriptBuf::from_bytes(vec![0x61; 2]);
+        for field in [
+            BlockField::NonMinimalHeight,
+            BlockField::WrongHeight,
+            BlockField::ExcessiveCoinbaseValue,
+        ] {
+            assert!(BlockBreaker::break_single_field(&no_height, field.clone()).is_err());
+            let config = ProcessingConfig {
+                fields_to_modify: vec![field],
+                coinbase_height: Some(height),
+                ..Default::default()
+            };
+            assert!(BlockBreaker::break_with_config(&no_height, config).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_pow_breakers() {
+        use bitcoin::hashes::Hash;
+        use bitcoin::params::Params;
+        use bitcoin::{CompactTarget, Network, Target};
+        use misfit_core::block::generator::GenerateBlock;
+        use misfit_core::breakers::block::{BlockBreaker, BlockField, PowProcessor};
+
+        let mut block = bitcoin::constants::genesis_block(Network::Regtest);
+        block.header.time += 1;
+        block.header = GenerateBlock::mine(block.header).unwrap();
+        assert!(block.header.validate_pow(block.header.target()).is_ok());
+
+        let broken = BlockBreaker::break_single_field(&block, BlockField::HighHash).unwrap();
+        let target = broken.header.target();
+        let hash = Target::from_le_bytes(broken.header.block_hash().to_byte_array());
+        // Misses the target by at most a factor of two.
+        let mut doubled = [0u8; 32];
+        let mut carry = 0u8;
+        for (byte, target_byte) in doubled.iter_mut().zip(target.to_le_bytes()) {
+            *byte = (target_byte << 1) | carry;
+            carry = target_byte >> 7;
+        }
+        assert_eq!(carry, 0);
+        assert!(hash > target);
+        assert!(hash <= Target::from_le_bytes(doubled));
+        assert_eq!(broken.header.bits, block.header.bits);
+        assert_eq!(broken.header.merkle_root, block.header.merkle_root);
+
+        assert_eq!(PowProcessor::above_pow_limit(Network::Bitcoin).to_consensus(), 0x1d010000);
+        assert_eq!(PowProcessor::above_pow_limit(Network::Regtest).to_consensus(), 0x21008000);
+        for network in [Network::Bitcoin, Network::Signet, Network::Regtest] {
+            let bits = PowProcessor::above_pow_limit(network);
+            assert!(Target::from_compact(bits) > Params::new(network).max_attainable_target);
+        }
+
+        let negative = PowProcessor::negative(CompactTarget::from_consensus(0x1d00ffff));
+        assert_eq!(negative.to_consensus(), 0x1d80ffff);
+        let negative = PowProcessor::negative(CompactTarget::from_consensus(0x1d000000));
+        assert_eq!(negative.to_consensus(), 0x1d800001);
+        assert_eq!(Target::from_compact(PowProcessor::zero()), Target::ZERO);
+        assert!(PowProcessor::overflow().to_consensus() >> 24 > 34);
+
+        let broken = BlockBreaker::break_single_field(&block, BlockField::ZeroBits).unwrap();
+        assert!(broken.header.validate_pow(broken.header.target()).is_err());
+
+        // Header only breaks are mined again, so nodes get past the proof of work check.
+        for field in [BlockField::Version, BlockField::Timestamp, BlockField::TimeTooNew] {
+            let broken = BlockBreaker::break_single_field(&block, field.clone()).unwrap();
+            assert_ne!(broken.header, block.header, "{:?}", field);
+            assert!(broken.header.validate_pow(broken.header.target()).is_ok(), "{:?}", field);
+        }
+    }
+
+    #[test]
+    fn test_bits_processor_target_arithmetic() {
+        use bitcoin::{Network, Target, Work};
+        use misfit_core::breakers::block::BitsProcessor;
+
+        assert_eq!(BitsProcessor::bits_to_target(0x1d00ffff), Target::MAX_ATTAINABLE_MAINNET);
+        assert_eq!(BitsProcessor::target_to_bits(Target::MAX_ATTAINABLE_MAINNET), 0x1d00ffff);
+        assert_eq!(BitsProcessor::increase_difficulty(0x1d00ffff, 2.0), 0x1c7fff80);
+        assert_eq!(BitsProcessor::decrease_difficulty(0x1c7fff80, 2.0), 0x1d00ffff);
+        assert_eq!(BitsProcessor::increase_difficulty(0x207fffff, 4.0), 0x201fffff);
+        assert_eq!(BitsProcessor::decrease_difficulty(0x1f7fffff, 4.0), 0x2001ffff);
+        // Saturates at 2^256 - 1.
+        assert_eq!(BitsProcessor::decrease_difficulty(0x207fffff, 4.0), 0x2100ffff);
+        assert_eq!(BitsProcessor::increase_difficulty(0x1d00ffff, -1.0), 0x1d00ffff);
+
+        // Core's SetCompact flags.
+        let decoded = BitsProcessor::decode_compact(0x04923456);
+        assert!(decoded.negative && !decoded.overflow);
+        assert!(!BitsProcessor::decode_compact(0x04800000).negative);
+        assert!(BitsProcessor::decode_compact(0xff123456).overflow);
+        assert!(BitsProcessor::decode_compact(0x21010000).overflow);
+        assert!(!BitsProcessor::decode_compact(0x2100ffff).overflow);
+        assert_eq!(BitsProcessor::bits_to_target(0x01123456), Target::from_le_bytes([
+            0x12, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        ]));
+
+        assert!(BitsProcessor::is_valid_bits(0x1d00ffff));
+        assert!(!BitsProcessor::is_valid_bits(0x1d80ffff));
+        assert!(!BitsProcessor::is_valid_bits(0x01003456));
+        assert!(BitsProcessor::is_within_pow_limit(0x1c7fff80, Network::Bitcoin));
+        assert!(!BitsProcessor::is_within_pow_limit(0x1d010000, Network::Bitcoin));
+        assert!(BitsProcessor::is_min_difficulty(0x207fffff));
+        assert!(!BitsProcessor::is_min_difficulty(0x1d00ffff));
+
+        // The genesis block carries 0x100010001 work.
+        let genesis = bitcoin::constants::genesis_block(Network::Bitcoin).header;
+        let mut expected = [0u8; 32];
+        expected[24..].copy_from_slice(&0x100010001u64.to_be_bytes());
+        assert_eq!(BitsProcessor::work(0x1d00ffff), Work::from_be_bytes(expected));
+        expected[24..].copy_from_slice(&0x200020002u64.to_be_bytes());
+        assert_eq!(BitsProcessor::chainwork(&[genesis, genesis]), Work::from_be_bytes(expected));
+
+        // Invalid bits carry no work, and the total saturates instead of overflowing.
+        let zero = Work::from_le_bytes([0u8; 32]);
+        for bits in [0x01003456, 0x1d80ffff, 0x23000001] {
+            assert_eq!(BitsProcessor::work(bits), zero, "{:#x}", bits);
+        }
+        let mut invalid = genesis;
+        invalid.bits = bitcoin::CompactTarget::from_consensus(0x01003456);
+        assert_eq!(BitsProcessor::chainwork(&[genesis, invalid]), BitsProcessor::work(0x1d00ffff));
+        let mut hardest = genesis;
+        hardest.bits = bitcoin::CompactTarget::from_consensus(0x01010000);
+        assert_eq!(
+            BitsProcessor::chainwork(&[hardest, hardest, hardest]),
+            Work::from_le_bytes([0xff; 32])
+        );
+    }
+
+    #[test]
+    fn test_break_block_header_with_explicit_values() {
+        use bitcoin::block::Header;
+        use bitcoin::consensus::encode;
+        use bitcoin::hashes::Hash;
+        use bitcoin::{BlockHash, TxMerkleNode};
+        use misfit_core::block::generator::GenerateBlock;
+        use misfit_core::block::random::block::BlockParams;
+
+        let block = GenerateBlock::valid_random(BlockParams::default());
+        let header = block.header;
+        let prev_blockhash = BlockHash::from_byte_array([0x11; 32]);
+
+        let result = Generator::break_block(
+            encode::serialize_hex(&header),
+            ["--prev-hash", "--merkle-root", "--timestamp", "--bits", "--nonce"]
+                .map(String::from)
+                .to_vec(),
+            vec![
+                format!("--prev-hash-override={}", prev_blockhash),
+                "--flip-prev-hash=0,255".to_string(),
+                "--flip-merkle-root=8".to_string(),
+                "--time-override=1700000000".to_string(),
+                "--timestamp-offset=-60".to_string(),
+                "--bits-override=0x1d00ffff".to_string(),
+                "--nonce-override=42".to_string(),
+            ],
+        );
+        let hex = result.split("Broken Block Header (Hex):\n").nth(1).unwrap().trim();
+        let broken: Header = encode::deserialize_hex(hex).unwrap();
+
+        let mut expected = [0x11; 32];
+        expected[0] ^= 0x01;
+        expected[31] ^= 0x80;
+        assert_eq!(broken.prev_blockhash, BlockHash::from_byte_array(expected));
+
+        let mut expected = header.merkle_root.to_byte_array();
+        expected[1] ^= 0x01;
+        assert_eq!(broken.merkle_root, TxMerkleNode::from_byte_array(expected));
+
+        assert_eq!(broken.time, 1_699_999_940);
+        assert_eq!(broken.bits.to_consensus(), 0x1d00ffff);
+        assert_eq!(broken.nonce, 42);
+        assert_eq!(broken.version, header.version);
+    }
+
+    #[test]
+    fn test_header_chain_breakers() {
+        use misfit_core::block::generator::GenerateBlock;
+        use misfit_core::block::random::chain::ChainParams;
+        use misfit_core::breakers::block::{BitsProcessor, ChainBreak, ChainProcessor};
+
+        let chain = GenerateBlock::header_chain(ChainParams {
+            length: Some(6),
+            ..Default::default()
+        }).unwrap();
+        let hashes: Vec<_> = chain.headers.iter().map(|header| header.block_hash()).collect();
+
+        let orphan = ChainProcessor::orphan(&chain).unwrap();
+        assert_eq!(orphan.len(), 6);
+        assert!(ChainProcessor::is_continuous(&orphan));
+        assert!(!hashes.contains(&orphan[0].prev_blockhash));
+        assert_ne!(orphan[0].prev_blockhash, chain.headers[0].prev_blockhash);
+
+        let fork = ChainProcessor::fork_below_checkpoint(&chain, 3).unwrap();
+        assert!(ChainProcessor::is_continuous(&fork));
+        assert_eq!(fork[0].prev_blockhash, hashes[1]);
+        assert_ne!(fork[0].block_hash(), hashes[2]);
+        assert!(BitsProcessor::chainwork(&fork) > BitsProcessor::chainwork(&chain.headers[2..]));
+        assert!(ChainProcessor::fork_below_checkpoint(&chain, 0).is_err());
+
+        let weaker = ChainProcessor::lower_work(&chain).unwrap();
+        assert!(ChainProcessor::is_continuous(&weaker));
+        assert_eq!(weaker[0].prev_blockhash, chain.headers[0].prev_blockhash);
+        assert!(BitsProcessor::chainwork(&weaker) < BitsProcessor::chainwork(&chain.headers));
+
+        let duplicated = ChainProcessor::process_chain(&chain, ChainBreak::DuplicateHeader, Some(2))
+            .unwrap();
+        assert_eq!(duplicated.len(), 7);
+        assert_eq!(duplicated[2], duplicated[3]);
+        assert!(!ChainProcessor::is_continuous(&duplicated));
+
+        let gap = ChainProcessor::process_chain(&chain, ChainBreak::NonContinuous, None).unwrap();
+        assert_eq!(gap.len(), 5);
+        assert!(!ChainProcessor::is_continuous(&gap));
+        assert!(ChainProcessor::non_continuous(&chain, 5).is_err());
+
+        let result =
+            Generator::header_chain(Some(4), Some("orphan".to_string()), None, None, None, None);
+        assert_eq!(result.split("Broken headers:\n").nth(1).unwrap().lines().count(), 4);
+
+        // Built on the regtest genesis, the chain connects to a fresh node.
+        let genesis = bitcoin::constants::genesis_block(bitcoin::Network::Regtest).header;
+        let start_time = genesis.time + 600;
+        let result = Generator::header_chain(
+            Some(2),
+            None,
+            None,
+            Some(genesis.block_hash().to_string()),
+            Some(start_time),
+            Some(genesis.time.to_string()),
+        );
+        let first = result.strip_prefix("Chain:\n").unwrap().lines().next().unwrap();
+        let first: bitcoin::block::Header =
+            bitcoin::consensus::encode::deserialize_hex(first).unwrap();
+        assert_eq!(first.prev_blockhash, genesis.block_hash());
+        assert!(first.time >= start_time);
+        let result = Generator::header_chain(None, None, None, Some("zz".into()), None, None);
+        assert!(result.starts_with("Error decoding previous block hash"), "{}", result);
+        let result = Generator::header_chain(None, None, None, None, None, Some("1,x".into()));
+        assert!(result.starts_with("Error decoding previous times"), "{}", result);
+    }
+
+    #[test]
+    fn test_timestamp_breakers_against_ancestors() {
+        use bitcoin::block::Header;
+        use bitcoin::consensus::encode;
+        use bitcoin::Network;
+        use misfit_core::block::generator::GenerateBlock;
+        use misfit_core::block::random::chain::ChainParams;
+        use misfit_core::breakers::block::{TimestampBreak, TimestampProcessor};
+
+        let prev_times = [100, 700, 300, 1300, 500, 1900, 1100, 2500, 900, 3100, 1500, 3700];
+        let broken_time = |mode| TimestampProcessor::broken_time(&prev_times, mode, Some(10_000));
+        // Median of the last 11 timestamps.
+        assert_eq!(broken_time(TimestampBreak::MedianTimePast), Some(1300));
+        assert_eq!(broken_time(TimestampBreak::BelowMedianTimePast), Some(1299));
+        assert_eq!(broken_time(TimestampBreak::TooNew), Some(17_201));
+        assert_eq!(broken_time(TimestampBreak::Timewarp), Some(3099));
+        assert_eq!(TimestampProcessor::broken_time(&[], TimestampBreak::Timewarp, None), None);
+
+        assert!(TimestampProcessor::is_retarget_height(4032, Network::Testnet4));
+        assert!(!TimestampProcessor::is_retarget_height(4033, Network::Testnet4));
+
+        let chain = GenerateBlock::header_chain(ChainParams::default()).unwrap();
+        let next = TimestampProcessor::next_header(&chain, TimestampBreak::MedianTimePast, None)
+            .unwrap();
+        assert_eq!(next.prev_blockhash, chain.tip().unwrap().block_hash());
+        assert_eq!(Some(next.time), chain.median_time_past());
+        assert!(next.target().is_met_by(next.block_hash()));
+
+        let times = prev_times.map(|time| time.to_string()).join(",");
+        let header = GenerateBlock::header_chain(ChainParams::default()).unwrap().headers[0];
+        let result = Generator::break_block(
+            encode::serialize_hex(&header),
+            vec!["--timewarp".to_string()],
+            vec![format!("--prev-times={}", times)],
+        );
+        assert!(result.contains("Timewarp"));
+        let hex = result.split("Broken Block Header (Hex):\n").nth(1).unwrap().trim();
+        let broken: Header = encode::deserialize_hex(hex).unwrap();
+        assert_eq!(broken.time, 3099);
+
+        // Without ancestors the rules cannot be broken, which is reported instead.
+        for flag in ["--time-at-mtp", "--time-below-mtp", "--timewarp"] {
+            let result = Generator::break_block(
+                encode::serialize_hex(&header),
+                vec![flag.to_string()],
+                vec![],
+            );
+            assert!(result.starts_with("Error breaking block"), "{}: {}", flag, result);
+        }
+    }
+
+    #[test]
+    fn test_version_breakers_by_height_and_network() {
+        use bitcoin::block::{Header, Version};
+        use bitcoin::consensus::encode;
+        use bitcoin::Network;
+        use misfit_core::block::generator::GenerateBlock;
+        use misfit_core::block::random::block::BlockParams;
+        use misfit_core::block::random::version::VersionBits;
+        use bitcoin::Block;
+        use misfit_core::breakers::block::block::{BlockBreaker, BlockField, ProcessingConfig};
+        use misfit_core::breakers::block::version::KNOWN_DEPLOYMENT_BITS;
+        use misfit_core::breakers::block::VersionProcessor;
+        use misfit_core::transaction::random::coinbase::CoinbaseParams;
+
+        // Mainnet activation heights of BIP34, BIP66 and BIP65.
+        assert_eq!(VersionProcessor::outdated(227_931, Network::Bitcoin).unwrap(), 1);
+        assert_eq!(VersionProcessor::outdated(363_725, Network::Bitcoin).unwrap(), 2);
+        assert_eq!(VersionProcessor::outdated(388_381, Network::Bitcoin).unwrap(), 3);
+        assert_eq!(VersionProcessor::outdated(1, Network::Regtest).unwrap(), 3);
+        assert_eq!(VersionProcessor::process_version(4, None), 3);
+        // Before BIP34 there is no outdated version.
+        assert!(VersionProcessor::outdated(227_930, Network::Bitcoin).is_err());
+        assert!(VersionProcessor::outdated(0, Network::Regtest).is_err());
+
+        let version = Version::from_consensus(VersionProcessor::unknown_deployment());
+        assert!(VersionBits::has_top_bits(version));
+        for bit in KNOWN_DEPLOYMENT_BITS {
+            assert!(!version.is_signalling_soft_fork(bit));
+        }
+
+        let header = GenerateBlock::valid_random(BlockParams::default()).header;
+        let result = Generator::break_block(
+            encode::serialize_hex(&header),
+            vec!["--version".to_string()],
+            vec!["--coinbase-height=300000".to_string(), "--network=bitcoin".to_string()],
+        );
+        let hex = result.split("Broken Block Header (Hex):\n").nth(1).unwrap().trim();
+        let broken: Header = encode::deserialize_hex(hex).unwrap();
+        assert_eq!(broken.version.to_consensus(), 1);
+
+        // Full blocks break against the height of their coinbase.
+        let at_height = |height| Block {
+            header,
+            txdata: vec![GenerateTx::coinbase(CoinbaseParams {
+                height: Some(height),
+                ..Default::default()
+            })],
+        };
+        let config = |network| ProcessingConfig {
+            fields_to_modify: vec![BlockField::Version],
+            network: Some(network),
+            ..Default::default()
+        };
+        let broken =
+            BlockBreaker::break_with_config(&at_height(363_725), config(Network::Bitcoin)).unwrap();
+        assert_eq!(broken.header.version.to_consensus(), 2);
+        assert!(BlockBreaker::break_with_config(&at_height(0), config(Network::Regtest)).is_err());
+    }
+
+    #[test]
+    fn test_break_transaction_targets_single_input_and_output() {
+        use std::collections::HashSet;
+
+        use bitcoin::consensus::encode;
+        use bitcoin::Transaction;
+        use misfit_core::breakers::transaction::{
+            InvalidationConfig, InvalidationFlag, TransactionInvalidator,
+        };
+        use misfit_core::transaction::generator::GenerateTx;
+        use misfit_core::transaction::random::transaction::TxParams;
+
+        let mut tx = GenerateTx::valid_random(TxParams::default());
+        while tx.input.len() < 3 {
+            tx.input.push(tx.input[0].clone());
+        }
+        while tx.output.len() < 2 {
+            tx.output.push(tx.output[0].clone());
+        }
+
+        let broken = TransactionInvalidator::invalidate_with_config(
+            tx.clone(),
+            &InvalidationConfig {
+                flags: HashSet::from([
+                    InvalidationFlag::WitnessData,
+                    InvalidationFlag::InputTxid,
+                    InvalidationFlag::OutputAmount,
+                ]),
+                input_indices: vec![2],
+                output_indices: vec![0],
+                ..Default::default()
+            },
+        ).unwrap();
+        assert_eq!(broken.input.len(), tx.input.len());
+        assert_eq!(broken.input[..2], tx.input[..2]);
+        assert_ne!(broken.input[2].witness, tx.input[2].witness);
+        assert_ne!(broken.input[2].previous_output.txid, tx.input[2].previous_output.txid);
+        assert_ne!(broken.output[0].value, tx.output[0].value);
+        assert_eq!(broken.output[1..], tx.output[1..]);
+
+        let result = Generator::break_transaction(
+            encode::serialize_hex(&tx),
+            vec!["--sequence".to_string()],
+            vec!["--input-index=1".to_string()],
+        );
+        assert!(result.contains("Input Indices: [1]"));
+        let hex = result.split("Invalidated Raw Transaction:\n").nth(1).unwrap();
+        let broken: Transaction = encode::deserialize_hex(hex.trim().trim_matches('"')).unwrap();
+        assert_ne!(broken.input[1].sequence, tx.input[1].sequence);
+        assert_eq!(broken.input[0], tx.input[0]);
+        assert_eq!(broken.input[2], tx.input[2]);
+
+        // Indices past the inputs or outputs are reported instead of ignored.
+        let out_of_range = |input_indices, output_indices| {
+            TransactionInvalidator::invalidate_with_config(
+                tx.clone(),
+                &InvalidationConfig {
+                    flags: HashSet::from([InvalidationFlag::All]),
+                    input_indices,
+                    output_indices,
+                    ..Default::default()
+                },
+            )
+        };
+        assert!(out_of_range(vec![0, tx.input.len()], vec![]).is_err());
+        assert!(out_of_range(vec![], vec![tx.output.len()]).is_err());
+        let result = Generator::break_transaction(
+            encode::serialize_hex(&tx),
+            vec!["--sequence".to_string()],
+            vec![format!("--input-index={}", tx.input.len())],
+        );
+        assert!(result.starts_with("Error invalidating transaction"), "{}", result);
+    }
+
+    #[test]
+    fn test_signature_breakers() {
+        use bitcoin::key::Keypair;
+        use bitcoin::secp256k1::{ecdsa, Message, Secp256k1, SecretKey};
+        use bitcoin::Witness;
+        use misfit_core::breakers::transaction::{
+            InvalidationFlag, SignatureBreak, SignatureProcessor, UNDEFINED_SIGHASH_TYPE,
+        };
+
+        let secp = Secp256k1::new();
+        let key = SecretKey::from_slice(&[0x42; 32]).unwrap();
+        let message = Message::from_digest([0x07; 32]);
+
+        let mut signature = secp.sign_ecdsa(&message, &key);
+        signature.normalize_s();
+        let mut ecdsa_sig = signature.serialize_der().to_vec();
+        ecdsa_sig.push(0x01);
+
+        let parse = |sig: &[u8]| ecdsa::Signature::from_der(&sig[..sig.len() - 1]);
+        let broken = |mode| SignatureProcessor::break_ecdsa(&ecdsa_sig, mode).unwrap();
+
+        assert!(SignatureProcessor::is_strict_der(&ecdsa_sig));
+        let high_s = parse(&broken(SignatureBreak::HighS)).unwrap();
+        assert_ne!(high_s, signature);
+        let mut normalized = high_s;
+        normalized.normalize_s();
+        assert_eq!(normalized, signature);
+
+        for mode in [
+            SignatureBreak::DerPadding,
+            SignatureBreak::NegativeR,
+            SignatureBreak::WrongDerLength,
+        ] {
+            assert!(!SignatureProcessor::is_strict_der(&broken(mode)));
+            assert_eq!(broken(mode).last(), Some(&0x01));
+        }
+
+        let wrong_type = broken(SignatureBreak::WrongSighashType);
+        assert_eq!(wrong_type[..wrong_type.len() - 1], ecdsa_sig[..ecdsa_sig.len() - 1]);
+        assert_eq!(wrong_type.last(), Some(&UNDEFINED_SIGHASH_TYPE));
+
+        let wrong_message = parse(&broken(SignatureBreak::WrongMessage)).unwrap();
+        let pubkey = key.public_key(&secp);
+        assert!(secp.verify_ecdsa(&message, &wrong_message, &pubkey).is_err());
+        assert!(SignatureProcessor::break_ecdsa(&ecdsa_sig, SignatureBreak::SchnorrInvalidR)
+            .is_none());
+
+        let keypair = Keypair::from_secret_key(&secp, &key);
+        let schnorr_sig = secp.sign_schnorr(&message, &keypair).as_ref().to_vec();
+        let broken = |mode| SignatureProcessor::break_schnorr(&schnorr_sig, mode).unwrap();
+        assert_eq!(broken(SignatureBreak::SchnorrInvalidR)[..32], [0xff; 32]);
+        let explicit_default = [&schnorr_sig[..], &[0x00]].concat();
+        assert_eq!(broken(SignatureBreak::SchnorrDefaultSighash), explicit_default);
+        assert_eq!(broken(SignatureBreak::WrongSighashType).len(), 65);
+        assert_eq!(broken(SignatureBreak::WrongMessage).len(), 64);
+
+        // Schnorr signatures are only looked for where a taproot spend carries them.
+        let script = vec![0x20; 34];
+        let mut control_block = vec![0xc0; 33];
+        control_block.extend([0x01; 32]);
+        let annex = vec![0x50, 0x01];
+        assert_eq!(SignatureProcessor::schnorr_index(std::slice::from_ref(&schnorr_sig)), Some(0));
+        assert_eq!(
+            SignatureProcessor::schnorr_index(&[schnorr_sig.clone(), annex]),
+            Some(0)
+        );
+        let script_path = [schnorr_sig.clone(), script.clone(), control_block.clone()];
+        assert_eq!(SignatureProcessor::schnorr_index(&script_path), Some(0));
+        let uncompressed = vec![0x04; 65];
+        assert_eq!(SignatureProcessor::schnorr_index(&[vec![0x30; 72], uncompressed]), None);
+        assert_eq!(SignatureProcessor::schnorr_index(&[vec![0x51], script, control_block]), None);
+
+        // Only the signature of the targeted input is touched.
+        let mut tx = random_tx();
+        tx.input.truncate(1);
+        let witness = [ecdsa_sig.clone(), pubkey.serialize().to_vec()];
+        tx.input[0].witness = Witness::from_slice(&witness);
+        tx.input.push(tx.input[0].clone());
+
+        assert_eq!(
+            InvalidationFlag::from_str("sig=der-padding"),
+            Some(InvalidationFlag::Signature(SignatureBreak::DerPadding))
+        );
+        let broken = break_and_decode(&tx, &["--sig=der-padding"], &["--input-index=1"]);
+        assert_eq!(broken.input[0], tx.input[0]);
+        assert!(!SignatureProcessor::is_strict_der(&broken.input[1].witness[0]));
+        assert_eq!(broken.input[1].witness[1], tx.input[1].witness[1]);
+    }
+
+    #[test]
+    fn test_script_mutations() {
+        use std::collections::HashSet;
+
+        use bitcoin::opcodes::all::{OP_ENDIF, OP_NOP, OP_PUSHDATA1, OP_RETURN};
+        use bitcoin::script::Instruction;
+        use bitcoin::ScriptBuf;
+        use misfit_core::breakers::transaction::{
+            mutate_script, InvalidationConfig, InvalidationFlag, ScriptBreak,
+            TransactionInvalidator, DISABLED_OPCODES, MAX_OPS_PER_SCRIPT,
+            MAX_SCRIPT_ELEMENT_SIZE, MAX_SCRIPT_SIZE,
+        };
+
+        let script = ScriptBuf::from_hex("76a914000000000000000000000000000000000000000088ac")
+            .unwrap();
+        let mutate = |mode| mutate_script(&script, mode);
+
+        let disabled = mutate(ScriptBreak::DisabledOpcode);
+        assert!(DISABLED_OPCODES.iter().any(|op| op.to_u8() == disabled.as_bytes()[0]));
+        assert!(disabled.as_bytes().ends_with(script.as_bytes()));
+
+        assert_eq!(mutate(ScriptBreak::OpReturn).as_bytes()[0], OP_RETURN.to_u8());
+        let non_minimal = mutate(ScriptBreak::NonMinimalPush);
+        assert_eq!(non_minimal.as_bytes()[..3], [OP_PUSHDATA1.to_u8(), 1, 1]);
+        match mutate(ScriptBreak::OversizedPush).instructions().next() {
+            Some(Ok(Instruction::PushBytes(push))) => {
+                assert_eq!(push.len(), MAX_SCRIPT_ELEMENT_SIZE + 1)
+            }
+            other => panic!("unexpected instruction {:?}", other),
+        }
+        let unbalanced = mutate(ScriptBreak::UnbalancedConditional);
+        assert_eq!(unbalanced.as_bytes().last(), Some(&OP_ENDIF.to_u8()));
+
+        let nops = mutate(ScriptBreak::TooManyOps)
+            .as_bytes()
+            .iter()
+            .filter(|byte| **byte == OP_NOP.to_u8())
+            .count();
+        assert!(nops > MAX_OPS_PER_SCRIPT);
+
+        let oversized = mutate(ScriptBreak::OversizedScript);
+        assert!(oversized.len() > MAX_SCRIPT_SIZE);
+        assert!(oversized.instructions().all(|instruction| instruction.is_ok()));
+
+        let mut tx = random_tx();
+        while tx.output.len() < 2 {
+            tx.output.push(tx.output[0].clone());
+        }
+        let broken = TransactionInvalidator::invalidate_with_config(
+            tx.clone(),
+            &InvalidationConfig {
+                flags: HashSet::from([
+                    InvalidationFlag::from_str("script-pubkey=op-return").unwrap(),
+                    InvalidationFlag::from_str("script-sig=unbalanced-if").unwrap(),
+                ]),
+                output_indices: vec![1],
+                ..Default::default()
+            },
+        ).unwrap();
+        assert_eq!(broken.output[0], tx.output[0]);
+        assert_eq!(broken.output[1].script_pubkey.as_bytes()[0], OP_RETURN.to_u8());
+        for (broken, input) in broken.input.iter().zip(&tx.input) {
+            assert_eq!(broken.script_sig.len(), input.script_sig.len() + 1);
+        }
+    }
+
+    #[test]
+    fn test_witness_breakers() {
+        use bitcoin::consensus::encode;
+        use bitcoin::opcodes::all::OP_NOP;
+        use bitcoin::{TxIn, Witness};
+        use misfit_core::breakers::transaction::{
+            break_witness, WitnessBreak, ANNEX_TAG, MAX_STANDARD_P2WSH_STACK_ITEMS,
+            MAX_STANDARD_P2WSH_STACK_ITEM_SIZE,
+        };
+
+        let input = TxIn {
+            witness: Witness::from_slice(&[vec![0xaa; 72], vec![0x51, 0x87]]),
+            ..Default::default()
+        };
+        let broken = |mode| {
+            let mut input = input.clone();
+            break_witness(&mut input, mode);
+            input.witness.to_vec()
+        };
+        let items = input.witness.to_vec();
+
+        assert_eq!(broken(WitnessBreak::ExtraElement)[1..], items[..]);
+        assert_eq!(broken(WitnessBreak::DropLastElement), items[..1]);
+        assert_eq!(broken(WitnessBreak::SwapElements), [items[1].clone(), items[0].clone()]);
+        assert_eq!(broken(WitnessBreak::ScriptMismatch)[1], [0x51, 0x87, OP_NOP.to_u8()]);
+
+        let too_many = broken(WitnessBreak::TooManyElements);
+        assert!(too_many.len() - 1 > MAX_STANDARD_P2WSH_STACK_ITEMS);
+        assert_eq!(too_many.last(), items.last());
+
+        let oversized = broken(WitnessBreak::OversizedElement);
+        assert_eq!(oversized[0].len(), MAX_STANDARD_P2WSH_STACK_ITEM_SIZE + 1);
+        assert_eq!(broken(WitnessBreak::Annex).last(), Some(&vec![ANNEX_TAG]));
+
+        // Modes that cannot apply leave the witness alone and say so.
+        let mut unchanged = input.clone();
+        assert!(!break_witness(&mut unchanged, WitnessBreak::UnexpectedWitness));
+        assert_eq!(unchanged, input);
+        let single = Witness::from_slice(&[vec![0x51]]);
+        assert!(!WitnessBreak::SwapElements.applies_to(&single));
+        for mode in [WitnessBreak::DropLastElement, WitnessBreak::ScriptMismatch] {
+            assert!(!mode.applies_to(&Witness::default()));
+        }
+
+        let mut tx = random_tx();
+        tx.input.truncate(1);
+        tx.input[0].witness = Witness::default();
+        let broken = break_and_decode(&tx, &["--witness=unexpected"], &[]);
+        assert!(!broken.input[0].witness.is_empty());
+        assert_eq!(broken.compute_txid(), tx.compute_txid());
+
+        let result = Generator::break_transaction(
+            encode::serialize_hex(&broken),
+            vec!["--witness=unexpected".to_string()],
+            vec![],
+        );
+        assert!(result.starts_with("Error invalidating transaction"), "{}", result);
+    }
+
+    #[test]
+    fn test_amount_breakers() {
+        use bitcoin::{Amount, ScriptBuf, TxOut};
+        use misfit_core::breakers::transaction::{
+            break_amount, exceed_inputs, AmountBreak, AMOUNT_SIGN_BIT,
+        };
+
+        let output = TxOut {
+            value: Amount::from_sat(50_000),
+            script_pubkey: ScriptBuf::from_bytes([[0x00, 0x14].as_slice(), &[0u8; 20]].concat()),
+        };
+        let broken = |mode| {
+            let mut output = output.clone();
+            break_amount(&mut output, mode);
+            output.value.to_sat()
+        };
+
+        assert_eq!(broken(AmountBreak::MaxMoney), Amount::MAX_MONEY.to_sat());
+        assert_eq!(broken(AmountBreak::AboveMaxMoney), Amount::MAX_MONEY.to_sat() + 1);
+        assert_eq!(broken(AmountBreak::Negative) as i64, 50_000 | AMOUNT_SIGN_BIT as i64);
+        assert!((broken(AmountBreak::Negative) as i64) < 0);
+        assert_eq!(broken(AmountBreak::Zero), 0);
+        assert!(broken(AmountBreak::Dust) < output.script_pubkey.minimal_non_dust().to_sat());
+
+        let mut tx = random_tx();
+        tx.input.truncate(1);
+        tx.output.truncate(1);
+
+        let overflow = break_and_decode(&tx, &["--amount=total-overflow"], &[]);
+        assert_eq!(overflow.output.len(), 2);
+        assert!(overflow.output.iter().all(|output| output.value == Amount::MAX_MONEY));
+
+        let exceed =
+            break_and_decode(&tx, &["--amount=exceed-inputs"], &["--prevout-value=100000"]);
+        let total: u64 = exceed.output.iter().map(|output| output.value.to_sat()).sum();
+        assert_eq!(total, 100_001);
+
+        // Missing or overflowing values are reported instead of panicking.
+        let mut outputs = tx.clone();
+        outputs.output.extend([tx.output[0].clone(), tx.output[0].clone()]);
+        for (tx, flags, config) in [
+            (&tx, vec!["--amount=exceed-inputs"], vec![]),
+            (&tx, vec!["--amount=exceed-inputs"], vec!["--prevout-value=18446744073709551615"]),
+            (
+                &outputs,
+                vec!["--amount=negative", "--amount=exceed-inputs"],
+                vec!["--prevout-value=1000"],
+            ),
+        ] {
+            let result = Generator::break_transaction(
+                bitcoin::consensus::encode::serialize_hex(tx),
+                flags.iter().map(|flag| flag.to_string()).collect(),
+                config.iter().map(|option| option.to_string()).collect(),
+            );
+            assert!(result.starts_with("Error invalidating transaction"), "{}", result);
+        }
+
+        let prevout_values = [Amount::from_sat(1_000)];
+        let mut no_output = tx.clone();
+        assert!(exceed_inputs(&mut no_output, &prevout_values, &[tx.output.len()]).is_err());
+        no_output.output.clear();
+        assert!(exceed_inputs(&mut no_output, &prevout_values, &[]).is_err());
+    }
+
+    #[test]
+    fn test_structure_breakers() {
+        use std::collections::HashSet;
+
+        use bitcoin::consensus::encode;
+        use bitcoin::OutPoint;
+        use misfit_core::breakers::transaction::{
+            break_structure, StructureBreak, MIN_STANDARD_TX_NONWITNESS_SIZE,
+        };
+
+        let tx = random_tx();
+        let broken = |mode, indices: &[usize]| {
+            let mut tx = tx.clone();
+            break_structure(&mut tx, mode, indices).unwrap();
+            tx
+        };
+
+        assert!(broken(StructureBreak::NoInputs, &[]).input.is_empty());
+        assert!(broken(StructureBreak::NoOutputs, &[]).output.is_empty());
+
+        let null = broken(StructureBreak::NullPrevout, &[]);
+        assert!(null.input.len() >= 2);
+        assert_eq!(null.input[0].previous_output, OutPoint::null());
+        assert!(!null.is_coinbase());
+
+        let undersized = broken(StructureBreak::Undersized, &[]);
+        assert!(undersized.base_size() < MIN_STANDARD_TX_NONWITNESS_SIZE);
+
+        let duplicate =
+            break_and_decode(&tx, &["--structure=duplicate-input"], &["--input-index=0"]);
+        let outpoints: HashSet<_> =
+            duplicate.input.iter().map(|input| input.previous_output).collect();
+        assert_eq!(duplicate.input.len(), tx.input.len() + 1);
+        assert_eq!(outpoints.len(), tx.input.len());
+
+        let mut out_of_range = tx.clone();
+        let past_last = [tx.input.len()];
+        let result = break_structure(&mut out_of_range, StructureBreak::DuplicateInput, &past_last);
+        assert!(result.is_err());
+        let result = break_structure(&mut out_of_range, StructureBreak::NullPrevout, &past_last);
+        assert!(result.is_err());
+
+        // Without inputs the legacy encoding is used, the output count following the empty
+        // input list instead of a segwit flag.
+        let no_inputs = broken(StructureBreak::NoInputs, &[]);
+        let hex = break_transaction_hex(&tx, &["--structure=no-inputs"], &[]);
+        let expected = [
+            encode::serialize(&no_inputs.version),
+            vec![0x00],
+            encode::serialize(&no_inputs.output),
+            encode::serialize(&no_inputs.lock_time),
+        ]
+        .concat();
+        assert_eq!(hex, hex::encode(expected));
+    }
+
+    #[test]
+    fn test_serialization_breakers() {
+        use bitcoin::consensus::encode;
+        use bitcoin::{Transaction, Witness};
+        use misfit_core::breakers::transaction::{
+            serialize_broken, SerializationBreak, SEGWIT_FLAG, SEGWIT_MARKER, TRAILING_BYTES,
+        };
+
+        let mut tx = random_tx();
+        tx.input[0].witness = Witness::from_slice(&[vec![0x01]]);
+        let broken = |modes: &[SerializationBreak]| serialize_broken(&tx, modes);
+
+        assert_eq!(broken(&[]), encode::serialize(&tx));
+        for mode in [
+            SerializationBreak::EmptyWitnesses,
+            SerializationBreak::WrongFlag,
+            SerializationBreak::WitnessCountMismatch,
+            SerializationBreak::TrailingBytes,
+            SerializationBreak::NonCanonicalVarint,
+        ] {
+            assert!(encode::deserialize::<Transaction>(&broken(&[mode])).is_err(), "{:?}", mode);
+        }
+
+        let wrong_flag = broken(&[SerializationBreak::WrongFlag]);
+        assert_eq!(wrong_flag[4..6], [SEGWIT_MARKER, SEGWIT_FLAG + 1]);
+        assert!(broken(&[SerializationBreak::TrailingBytes]).ends_with(&TRAILING_BYTES));
+        assert_eq!(broken(&[SerializationBreak::NonCanonicalVarint])[6], 0xfd);
+
+        let hex = break_transaction_hex(&tx, &["--serialization=trailing-bytes"], &[]);
+        assert!(hex.ends_with("deadbeef"));
+    }
+
+    #[test]
+    fn test_raw_mutator() {
+        use bitcoin::consensus::encode;
+        use bitcoin::{Block, Transaction, Witness};
+        use misfit_core::breakers::raw::{ParseTrace, RawMutation, RawMutator, HEADER_SIZE};
+
+        let mut tx = random_tx();
+        tx.input[0].witness = Witness::from_slice(&[vec![0x01; 72], vec![0x02; 33]]);
+        let bytes = encode::serialize(&tx);
+        let trace = ParseTrace::transaction(&bytes).unwrap();
+
+        assert_eq!(trace.fields.first().unwrap().name, "version");
+        assert_eq!(trace.fields.last().unwrap().end, bytes.len());
+        assert_eq!(trace.field("witness[0][1]").unwrap().len(), 33);
+        let script_pubkey = trace.field("output[0].script_pubkey").unwrap();
+        assert_eq!(
+            bytes[script_pubkey.start..script_pubkey.end],
+            tx.output[0].script_pubkey.to_bytes()[..]
+        );
+
+        let mutate = |name: &str, mutation: &str| {
+            let mutation = mutation.parse::<RawMutation>().unwrap();
+            RawMutator::mutate(&bytes, &trace, name, &mutation).unwrap()
+        };
+        for (name, mutation) in [
+            ("input_count", "flip"),
+            ("output[0].script_pubkey_len", "replace=ff"),
+            ("witness[0].count", "extend=00"),
+            ("locktime", "truncate"),
+        ] {
+            let mutated = mutate(name, mutation);
+            assert!(encode::deserialize::<Transaction>(&mutated).is_err(), "{}", name);
+        }
+        assert!("grow".parse::<RawMutation>().is_err());
+
+        let block_bytes = hex::decode(Generator::block(2).hex).unwrap();
+        let block_trace = ParseTrace::block(&block_bytes).unwrap();
+        assert_eq!(block_trace.field("tx_count").unwrap().start, HEADER_SIZE);
+        assert!(block_trace.field("tx[2].locktime").is_some());
+        let mutated = RawMutator::mutate(&block_bytes, &block_trace, "tx_count", &RawMutation::Flip);
+        assert!(encode::deserialize::<Block>(&mutated.unwrap()).is_err());
+
+        let result = Generator::mutate_raw(
+            encode::serialize_hex(&tx),
+            false,
+            Some("input[0].vout".to_string()),
+            Some("truncate".to_string()),
+        );
+        assert!(result.contains("Decoding Result: "));
+        assert!(!result.contains("still decodes"));
+    }
+
+    #[test]
+    fn test_finality_breakers() {
+        use bitcoin::absolute::{self, LockTime};
+        use bitcoin::opcodes::all::{OP_CLTV, OP_CSV, OP_DROP};
+        use bitcoin::script::Builder;
+        use bitcoin::{relative, Sequence, Witness};
+        use misfit_core::breakers::transaction::{
+            break_finality, FinalityBreak, InvalidationConfig, SEQUENCE_LOCKTIME_TYPE_FLAG,
+        };
+
+        let mut tx = random_tx();
+        tx.input[0].witness = Witness::default();
+        let config = InvalidationConfig {
+            tip_height: Some(800_000),
+            tip_median_time: Some(1_700_000_000),
+            prevout_confirmations: vec![5],
+            input_indices: vec![0],
+            ..Default::default()
+        };
+        let broken = |mode, tx: &Transaction| {
+            let mut tx = tx.clone();
+            break_finality(&mut tx, mode, &config).unwrap();
+            tx
+        };
+        let tip_height = absolute::Height::from_consensus(800_000).unwrap();
+        let tip_time = absolute::Time::from_consensus(1_700_000_000).unwrap();
+
+        let height = broken(FinalityBreak::HeightPastTip, &tx);
+        assert_eq!(height.lock_time, LockTime::from_height(800_001).unwrap());
+        assert!(height.is_lock_time_enabled());
+        assert!(!height.is_absolute_timelock_satisfied(tip_height, tip_time));
+
+        let time = broken(FinalityBreak::TimePastTip, &tx);
+        assert_eq!(time.lock_time, LockTime::from_time(1_700_000_001).unwrap());
+        assert!(time.input[0].sequence.enables_absolute_lock_time());
+
+        let final_sequences = broken(FinalityBreak::FinalSequences, &height);
+        assert!(final_sequences.input.iter().all(|input| input.sequence == Sequence::MAX));
+
+        let relative = broken(FinalityBreak::RelativeLockUnmet, &tx);
+        assert!(relative.version.0 >= 2);
+        assert_eq!(relative.input[0].sequence, Sequence::from_height(6));
+
+        // An existing height CLTV argument gets a time lock time, final at the tip
+        let mut cltv = tx.clone();
+        cltv.input[0].script_sig =
+            Builder::new().push_int(100).push_opcode(OP_CLTV).push_opcode(OP_DROP).into_script();
+        let cltv = broken(FinalityBreak::CltvTypeMismatch, &cltv);
+        assert!(matches!(cltv.lock_time, LockTime::Seconds(_)));
+        assert!(cltv.input[0].sequence.enables_absolute_lock_time());
+        assert!(cltv.is_absolute_timelock_satisfied(tip_height, tip_time));
+
+        // Without a CLTV argument, a time one is added against a zero lock time
+        let cltv = broken(FinalityBreak::CltvTypeMismatch, &tx);
+        assert_eq!(cltv.lock_time, LockTime::ZERO);
+        let expected = Builder::new().push_int(500_000_000).push_opcode(OP_CLTV).push_opcode(OP_DROP);
+        assert!(cltv.input[0].script_sig.as_bytes().starts_with(expected.as_bytes()));
+
+        // Without a CSV argument, a time one is added against a height sequence, both of zero
+        // so BIP68 is met
+        let csv = broken(FinalityBreak::CsvTypeMismatch, &tx);
+        let expected = Builder::new()
+            .push_int(SEQUENCE_LOCKTIME_TYPE_FLAG as i64)
+            .push_opcode(OP_CSV)
+            .push_opcode(OP_DROP);
+        assert!(csv.input[0].script_sig.as_bytes().starts_with(expected.as_bytes()));
+        let lock = csv.input[0].sequence.to_relative_lock_time().unwrap();
+        assert!(matches!(lock, relative::LockTime::Blocks(_)));
+        assert!(lock.is_satisfied_by(relative::Height::ZERO, relative::Time::ZERO));
+
+        // Checks cannot be added to witness inputs, nor a tip height guessed
+        let mut witness_input = tx.clone();
+        witness_input.input[0].witness = Witness::from_slice(&[vec![0x01]]);
+        for mode in [FinalityBreak::CltvTypeMismatch, FinalityBreak::CsvTypeMismatch] {
+            assert!(break_finality(&mut witness_input.clone(), mode, &config).is_err());
+        }
+        let no_tip = InvalidationConfig::default();
+        assert!(break_finality(&mut tx.clone(), FinalityBreak::HeightPastTip, &no_tip).is_err());
+    }
+
+    #[test]
+    fn test_version_breakers() {
+        use std::collections::HashSet;
+
+        use bitcoin::transaction::Version;
+        use bitcoin::{OutPoint, Sequence};
+        use clap::Parser;
+        use misfit_core::breakers::transaction::{
+            break_version, invalidate_version, InvalidationConfig, VersionBreak,
+            TX_MAX_STANDARD_VERSION,
+        };
+
+        assert_eq!(invalidate_version(Version(i32::MAX)), Version(TX_MAX_STANDARD_VERSION + 1));
+        assert_eq!(invalidate_version(Version(TX_MAX_STANDARD_VERSION + 1)), Version(0));
+
+        let mut tx = random_tx();
+        tx.input.truncate(1);
+        let parent = OutPoint::new(random_tx().compute_txid(), 0);
+        let config = InvalidationConfig {
+            truc_parent: Some(parent),
+            ..Default::default()
+        };
+        let broken = |mode| {
+            let mut tx = tx.clone();
+            break_version(&mut tx, mode, &config).unwrap();
+            tx
+        };
+
+        assert_eq!(broken(VersionBreak::Zero).version, Version(0));
+        assert_eq!(broken(VersionBreak::AboveStandard).version, Version(4));
+        assert_eq!(broken(VersionBreak::Negative).version, Version(-1));
+        assert_eq!(broken("7".parse().unwrap()).version, Version(7));
+
+        let version_one = broken(VersionBreak::RelativeLocksInVersionOne);
+        assert_eq!(version_one.version, Version::ONE);
+        assert_eq!(version_one.input[0].sequence, Sequence::from_height(1));
+
+        let truc = broken(VersionBreak::TrucTopology);
+        let parents: HashSet<_> = truc.input.iter().map(|input| input.previous_output.txid).collect();
+        assert_eq!(truc.version, Version(3));
+        assert_eq!(parents.len(), 2);
+        assert_eq!(truc.input.last().unwrap().previous_output, parent);
+        let no_parent = InvalidationConfig::default();
+        assert!(break_version(&mut tx.clone(), VersionBreak::TrucTopology, &no_parent).is_err());
+        let own_parent = InvalidationConfig {
+            truc_parent: Some(tx.input[0].previous_output),
+            ..Default::default()
+        };
+        assert!(break_version(&mut tx.clone(), VersionBreak::TrucTopology, &own_parent).is_err());
+
+        let exact = break_and_decode(&tx, &["--version=-3"], &[]);
+        assert_eq!(exact.version, Version(-3));
+        let truc_parent = format!("--truc-parent={}", parent);
+        let truc = break_and_decode(&tx, &["--version=truc"], &[&truc_parent]);
+        assert_eq!(truc.input.last().unwrap().previous_output, parent);
+
+        // Negative versions reach the breaker from the command line
+        let cli = crate::cli::Cli::try_parse_from([
+            "misfit_core",
+            "break-transaction",
+            "00",
+            "--break-version",
+            "-3",
+        ]);
+        assert!(cli.is_ok());
+    }
+}
@@ -1,3 +1,4 @@
+use super::random::coinbase::{CoinbaseParams, RandomCoinbase};
 use super::random::transaction::{RandomTransacion, TxParams};
 use bitcoin::Transaction;
 
@@ -7,4 +8,8 @@ impl GenerateTx {
     pub fn valid_random(params: TxParams) -> Transaction {
         Transaction::random(params)
     }
+
+    pub fn coinbase(params: CoinbaseParams) -> Transaction {
+        Transaction::coinbase(params)
+    }
 }
\ No newline at end of file
@@ -0,0 +1,115 @@
+use bitcoin::{
+    absolute::LockTime,
+    opcodes::all::OP_RETURN,
+    script::{Builder, PushBytesBuf},
+    transaction::Version,
+    Amount, OutPoint, ScriptBuf, Sequence, Transaction, TxIn, TxOut, Witness, WitnessCommitment,
+};
+use bitcoin::hashes::Hash;
+use secp256k1::rand::{self, Rng};
+
+use super::script::{RandomScript, ScriptParams, ScriptTypes};
+
+/// Prefix of the witness commitment pushed in a coinbase output (BIP141).
+pub const WITNESS_COMMITMENT_HEADER: [u8; 4] = [0xaa, 0x21, 0xa9, 0xed];
+
+/// Number of blocks between subsidy halvings.
+pub const SUBSIDY_HALVING_INTERVAL: u32 = 210_000;
+
+#[derive(Default)]
+pub struct CoinbaseParams {
+    pub height: Option<u32>,
+    /// Total paid by the first output, defaults to the block subsidy.
+    pub value: Option<Amount>,
+    pub script_pubkey: Option<ScriptBuf>,
+    /// Extra bytes appended to the scriptSig after the BIP34 height.
+    pub extra_nonce: Option<Vec<u8>>,
+    /// Adds the witness reserved value and a commitment output to be filled in later.
+    pub witness_commitment: Option<bool>,
+}
+
+pub trait RandomCoinbase {
+    fn coinbase(params: CoinbaseParams) -> Transaction;
+}
+
+impl RandomCoinbase for Transaction {
+    fn coinbase(params: CoinbaseParams) -> Transaction {
+        let height = params
+            .height
+            .unwrap_or_else(|| rand::thread_rng().gen_range(1..=1_000_000));
+
+        let extra_nonce = params
+            .extra_nonce
+            .unwrap_or_else(|| rand::thread_rng().gen::<[u8; 4]>().to_vec());
+
+        let script_sig = Builder::new()
+            .push_int(height as i64)
+            .push_slice(PushBytesBuf::try_from(extra_nonce).expect("extra nonce too long"))
+            .into_script();
+
+        let script_pubkey = params.script_pubkey.unwrap_or_else(|| {
+            ScriptBuf::random(ScriptParams {
+                script_type: Some(ScriptTypes::P2WPKH),
+                private_key: None,
+            })
+            .0
+        });
+
+        let mut output = vec![TxOut {
+            value: params.value.unwrap_or_else(|| block_subsidy(height)),
+            script_pubkey,
+        }];
+
+        let mut witness = Witness::default();
+        if params.witness_commitment.unwrap_or(false) {
+            witness.push([0u8; 32]);
+            output.push(TxOut {
+                value: Amount::ZERO,
+                script_pubkey: witness_commitment_script(&WitnessCommitment::all_zeros()),
+            });
+        }
+
+        Transaction {
+            version: Version::TWO,
+            lock_time: LockTime::ZERO,
+            input: vec![TxIn {
+                previous_output: OutPoint::null(),
+                script_sig,
+                sequence: Sequence::MAX,
+                witness,
+            }],
+            output,
+        }
+    }
+}
+
+/// Subsidy paid to a block at `height`, halving every `SUBSIDY_HALVING_INTERVAL` blocks.
+pub fn block_subsidy(height: u32) -> Amount {
+    let halvings = height / SUBSIDY_HALVING_INTERVAL;
+    if halvings >= 64 {
+        return Amount::ZERO;
+    }
+    Amount::from_sat(Amount::from_int_btc(50).to_sat() >> halvings)
+}
+
+/// `OP_RETURN <0xaa21a9ed || commitment>` as expected by `GetWitnessCommitmentIndex`.
+pub fn witness_commitment_script(commitment: &WitnessCommitment) -> ScriptBuf {
+    let mut data = WITNESS_COMMITMENT_HEADER.to_vec();
+    data.extend_from_slice(commitment.as_byte_array());
+
+    Builder::new()
+        .push_opcode(OP_RETURN)
+        .push_slice(PushBytesBuf::try_from(data).unwrap())
+        .into_script()
+}
+
+/// Index of the output holding the witness commitment, the last matching one wins.
+pub fn witness_commitment_index(coinbase: &Transaction) -> Option<usize> {
+    coinbase.output.iter().rposition(|output| {
+        let bytes = output.script_pubkey.as_bytes();
+        bytes.len() >= 38
+            && bytes[0] == OP_RETURN.to_u8()
+            && bytes[1] == 0x24
+            && bytes[2..6] == WITNESS_COMMITMENT_HEADER
+    })
+}
@@ -1,3 +1,4 @@
+pub mod coinbase;
 pub mod input;
 pub mod locktime;
 pub mod output;